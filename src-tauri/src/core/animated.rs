@@ -0,0 +1,171 @@
+// core/animated.rs
+// 动图剪贴板：剪贴板中的 GIF/APNG 原样保存，不解码为单帧 PNG，粘贴时写回原始数据，动画不会丢失
+// 同时保存第一帧作为静态缩略图，并在元数据中记录帧数和时长，供列表显示
+use clipboard_rs::{
+    common::RustImage, Clipboard, ClipboardContent, ClipboardContext, ContentFormat, RustImageData,
+};
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder},
+    AnimationDecoder, Frames, ImageFormat,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{fs, io::Cursor, time::Duration};
+use tauri::{AppHandle, Runtime};
+
+use crate::core::images::{images_dir, thumbnail_name};
+
+// 更大的动图按普通图片处理，只保存第一帧
+const MAX_ANIMATION_BYTES: usize = 32 * 1024 * 1024;
+
+// 各平台剪贴板中 GIF、PNG 的格式名称
+#[cfg(target_os = "windows")]
+const GIF_FORMATS: &[&str] = &["GIF"];
+#[cfg(target_os = "windows")]
+const PNG_FORMATS: &[&str] = &["PNG"];
+#[cfg(target_os = "macos")]
+const GIF_FORMATS: &[&str] = &["com.compuserve.gif"];
+#[cfg(target_os = "macos")]
+const PNG_FORMATS: &[&str] = &["public.png"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const GIF_FORMATS: &[&str] = &["image/gif"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PNG_FORMATS: &[&str] = &["image/apng", "image/png"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimationFormat {
+    Gif,
+    Apng,
+}
+
+impl AnimationFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            AnimationFormat::Gif => "gif",
+            AnimationFormat::Apng => "png",
+        }
+    }
+
+    fn clipboard_formats(self) -> &'static [&'static str] {
+        match self {
+            AnimationFormat::Gif => GIF_FORMATS,
+            AnimationFormat::Apng => PNG_FORMATS,
+        }
+    }
+}
+
+// 从剪贴板读取到的动图原始数据
+pub struct AnimatedImage {
+    pub format: AnimationFormat,
+    pub data: Vec<u8>,
+}
+
+fn frames(format: AnimationFormat, data: &[u8]) -> image::ImageResult<Frames<'_>> {
+    match format {
+        AnimationFormat::Gif => Ok(GifDecoder::new(Cursor::new(data))?.into_frames()),
+        AnimationFormat::Apng => Ok(PngDecoder::new(Cursor::new(data))?.apng()?.into_frames()),
+    }
+}
+
+// 只有一帧的 GIF 和普通 PNG 不是动图
+fn is_animated(format: AnimationFormat, data: &[u8]) -> bool {
+    if data.len() > MAX_ANIMATION_BYTES {
+        return false;
+    }
+    match format {
+        AnimationFormat::Gif => frames(format, data).is_ok_and(|frames| frames.take(2).count() > 1),
+        AnimationFormat::Apng => PngDecoder::new(Cursor::new(data))
+            .and_then(|decoder| decoder.is_apng())
+            .unwrap_or(false),
+    }
+}
+
+// 读取剪贴板中的动图，没有动图时返回 None，由调用方按普通图片读取
+pub fn read_animated(ctx: &ClipboardContext) -> Option<AnimatedImage> {
+    [AnimationFormat::Gif, AnimationFormat::Apng]
+        .into_iter()
+        .find_map(|format| {
+            format.clipboard_formats().iter().find_map(|name| {
+                if !ctx.has(ContentFormat::Other(name.to_string())) {
+                    return None;
+                }
+                let data = ctx.get_buffer(name).ok()?;
+                is_animated(format, &data).then_some(AnimatedImage { format, data })
+            })
+        })
+}
+
+// 保存动图原始数据和第一帧缩略图，返回文件名和记录的元数据
+pub fn save_animated<R: Runtime>(
+    app: &AppHandle<R>,
+    image: &AnimatedImage,
+    hash: &str,
+) -> Result<(String, Value), String> {
+    let dir = images_dir(app)?;
+    let file_name = format!("{}.{}", hash, image.format.extension());
+    let thumbnail = thumbnail_name(&file_name);
+
+    let mut count = 0;
+    let mut duration = Duration::ZERO;
+    let mut first = None;
+    for frame in frames(image.format, &image.data).map_err(|e| e.to_string())? {
+        let frame = frame.map_err(|e| e.to_string())?;
+        count += 1;
+        duration += Duration::from(frame.delay());
+        if first.is_none() {
+            first = Some(frame.into_buffer());
+        }
+    }
+    let first = first.ok_or("动图没有可显示的帧")?;
+
+    let path = dir.join(&file_name);
+    if !path.exists() {
+        fs::write(&path, &image.data).map_err(|e| e.to_string())?;
+    }
+    first
+        .save_with_format(dir.join(&thumbnail), ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+
+    let metadata = json!({
+        "animation": {
+            "format": image.format,
+            "frames": count,
+            "durationMs": duration.as_millis() as u64,
+            "thumbnail": thumbnail,
+        }
+    });
+    Ok((file_name, metadata))
+}
+
+// 记录元数据中的动图格式，普通图片返回 None
+pub fn animation_format(metadata: Option<&Value>) -> Option<AnimationFormat> {
+    metadata
+        .and_then(|metadata| metadata.get("animation"))
+        .and_then(|animation| animation.get("format"))
+        .and_then(|format| serde_json::from_value(format.clone()).ok())
+}
+
+// 把保存的动图写回剪贴板；GIF 同时写入第一帧，不支持 GIF 的应用粘贴为静态图片
+// APNG 本身兼容 PNG，不支持动画的应用会显示第一帧
+pub fn write_animated<R: Runtime>(
+    app: &AppHandle<R>,
+    ctx: &ClipboardContext,
+    file_name: &str,
+    format: AnimationFormat,
+) -> Result<(), String> {
+    let dir = images_dir(app)?;
+    let data = fs::read(dir.join(file_name)).map_err(|e| e.to_string())?;
+    let mut contents: Vec<ClipboardContent> = format
+        .clipboard_formats()
+        .iter()
+        .map(|name| ClipboardContent::Other(name.to_string(), data.clone()))
+        .collect();
+    if format == AnimationFormat::Gif {
+        let thumbnail = dir.join(thumbnail_name(file_name));
+        let image =
+            RustImageData::from_path(&thumbnail.to_string_lossy()).map_err(|e| e.to_string())?;
+        contents.push(ClipboardContent::Image(image));
+    }
+    ctx.set(contents).map_err(|e| e.to_string())
+}
@@ -0,0 +1,174 @@
+// core/anomaly.rs
+// 异常复制检测：一分钟内复制次数过多，或大量高熵文本（疑似密钥、编码后的数据）连续出现，
+// 可能是窃取剪贴板的恶意程序或失控的脚本，检测到时发送系统通知，并可自动暂停记录
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_notification::NotificationExt;
+
+const WINDOW: Duration = Duration::from_secs(60);
+// 同一次异常持续期间不重复通知
+const ALERT_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+// 每个字符的香农熵超过该值视为高熵文本，普通文字通常在 4 以下，随机密钥接近 6
+const HIGH_ENTROPY_BITS: f64 = 4.5;
+// 太短的文本熵值不稳定，不参与统计
+const MIN_ENTROPY_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnomalySettings {
+    pub enabled: bool,
+    // 一分钟内达到该复制次数视为异常，默认轮询间隔 500 毫秒时一分钟最多能发现 120 次
+    pub max_clips_per_minute: usize,
+    // 一分钟内达到该高熵文本数视为异常
+    pub max_high_entropy_per_minute: usize,
+    // 检测到异常时暂停剪贴板监听
+    pub auto_pause: bool,
+}
+
+impl Default for AnomalySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_clips_per_minute: 60,
+            max_high_entropy_per_minute: 20,
+            auto_pause: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnomalyKind {
+    Rate,
+    Entropy,
+}
+
+// clipboard-anomaly 事件的负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    // 最近一分钟内的次数
+    pub count: usize,
+    pub paused: bool,
+}
+
+#[derive(Default)]
+struct Tracker {
+    clips: VecDeque<Instant>,
+    high_entropy: VecDeque<Instant>,
+    last_alert: Option<Instant>,
+}
+
+// 保存最近一分钟的复制记录，由剪贴板监听在每次发现新内容时调用
+#[derive(Default)]
+pub struct AnomalyDetector {
+    tracker: Mutex<Tracker>,
+}
+
+// 每个字符的香农熵（bit）
+pub fn entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut total = 0;
+    for ch in text.chars() {
+        *counts.entry(ch).or_default() += 1;
+        total += 1;
+    }
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn push_and_count(events: &mut VecDeque<Instant>, now: Instant) -> usize {
+    events.push_back(now);
+    while events
+        .front()
+        .is_some_and(|time| now.duration_since(*time) > WINDOW)
+    {
+        events.pop_front();
+    }
+    events.len()
+}
+
+impl AnomalyDetector {
+    // 记录一次复制，text 为文本内容（其他类型为 None），达到阈值且不在冷却期内时返回异常
+    pub fn record(&self, text: Option<&str>, settings: &AnomalySettings) -> Option<Anomaly> {
+        if !settings.enabled {
+            return None;
+        }
+
+        let now = Instant::now();
+        let mut tracker = self.tracker.lock().unwrap();
+        let clips = push_and_count(&mut tracker.clips, now);
+        let high_entropy = match text {
+            Some(text)
+                if text.chars().count() >= MIN_ENTROPY_LEN
+                    && entropy(text) >= HIGH_ENTROPY_BITS =>
+            {
+                push_and_count(&mut tracker.high_entropy, now)
+            }
+            _ => 0,
+        };
+
+        let (kind, count) = if clips >= settings.max_clips_per_minute {
+            (AnomalyKind::Rate, clips)
+        } else if high_entropy >= settings.max_high_entropy_per_minute {
+            (AnomalyKind::Entropy, high_entropy)
+        } else {
+            return None;
+        };
+
+        if tracker
+            .last_alert
+            .is_some_and(|time| now.duration_since(time) < ALERT_COOLDOWN)
+        {
+            return None;
+        }
+        tracker.last_alert = Some(now);
+
+        Some(Anomaly {
+            kind,
+            count,
+            paused: settings.auto_pause,
+        })
+    }
+}
+
+// 发送系统通知和 clipboard-anomaly 事件
+pub fn report_anomaly<R: Runtime>(app: &AppHandle<R>, anomaly: &Anomaly) {
+    let reason = match anomaly.kind {
+        AnomalyKind::Rate => format!("一分钟内复制了 {} 次", anomaly.count),
+        AnomalyKind::Entropy => format!("一分钟内复制了 {} 段疑似密钥或编码数据", anomaly.count),
+    };
+    let action = if anomaly.paused {
+        "已暂停记录剪贴板，确认安全后可点击托盘菜单中的“恢复记录”。"
+    } else {
+        "请确认是否有程序在异常读写剪贴板。"
+    };
+    println!(
+        "Anomaly - {:?} detected, count {}",
+        anomaly.kind, anomaly.count
+    );
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("检测到异常的剪贴板活动")
+        .body(format!("{}，{}", reason, action))
+        .show()
+    {
+        println!("Anomaly - Failed to show notification: {:?}", e);
+    }
+    if let Err(e) = app.emit("clipboard-anomaly", anomaly) {
+        println!("Anomaly - Failed to emit event: {:?}", e);
+    }
+}
@@ -0,0 +1,214 @@
+// core/appearance.rs
+// 外观设置：主题模式（跟随系统/浅色/深色）、强调色、紧凑密度
+// 保存在应用设置中，并负责让窗口标题栏与 webview 的主题保持一致
+// 主窗口在启动时按设置创建：只有选择了窗口效果时才创建透明窗口，开关窗口效果后需要重启应用才能生效
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Emitter, Manager, Runtime, State, Theme, WebviewWindowBuilder, WindowEvent,
+};
+
+use crate::core::settings::{current_settings, update};
+use crate::core::tray::reload_tray_icon;
+use crate::core::window_effects::{apply_window_effect, WindowEffect};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+// 托盘图标样式：随托盘主题切换的单色图标、应用彩色图标、用户自定义图标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayIconStyle {
+    #[default]
+    Auto,
+    Color,
+    Custom,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Appearance {
+    pub theme: ThemeMode,
+    pub accent_color: String,
+    pub compact: bool,
+    // 窗口半透明效果，不支持的平台会自动回退为不透明
+    pub window_effect: WindowEffect,
+    pub tray_icon: TrayIconStyle,
+    // 自定义托盘图标文件路径（png/ico），仅在 tray_icon 为 Custom 时使用
+    pub custom_tray_icon: Option<String>,
+}
+
+impl Default for Appearance {
+    fn default() -> Self {
+        Self {
+            theme: ThemeMode::System,
+            accent_color: "#3b82f6".into(),
+            compact: false,
+            window_effect: WindowEffect::Off,
+            tray_icon: TrayIconStyle::Auto,
+            custom_tray_icon: None,
+        }
+    }
+}
+
+// 作为 Tauri 托管状态保存实际生效的窗口效果
+pub struct AppearanceState {
+    active_effect: Mutex<WindowEffect>,
+    // 主窗口创建时是否透明，不透明的窗口无法应用窗口效果
+    transparent: bool,
+}
+
+// theme-changed 事件的负载，theme 为实际生效的主题（light/dark）
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ThemeChangedPayload {
+    theme: Theme,
+    mode: ThemeMode,
+    accent_color: String,
+    compact: bool,
+    window_effect: WindowEffect,
+}
+
+// 将主题模式应用到主窗口，System 时交还给操作系统决定
+fn apply_theme<R: Runtime>(app: &AppHandle<R>, appearance: &Appearance) {
+    if let Some(window) = app.get_webview_window("main") {
+        let theme = match appearance.theme {
+            ThemeMode::System => None,
+            ThemeMode::Light => Some(Theme::Light),
+            ThemeMode::Dark => Some(Theme::Dark),
+        };
+        if let Err(e) = window.set_theme(theme) {
+            println!("Appearance - Failed to set window theme: {:?}", e);
+        }
+    }
+}
+
+// 按当前实际主题应用窗口效果（Mica 等效果需要区分深浅色）
+fn apply_effect<R: Runtime>(app: &AppHandle<R>, appearance: &Appearance) -> WindowEffect {
+    if !app.state::<AppearanceState>().transparent {
+        if appearance.window_effect != WindowEffect::Off {
+            println!("Appearance - Window is opaque, restart to apply the window effect");
+        }
+        return WindowEffect::Off;
+    }
+    match app.get_webview_window("main") {
+        Some(window) => apply_window_effect(
+            &window,
+            appearance.window_effect,
+            effective_theme(app, appearance),
+        ),
+        None => WindowEffect::Off,
+    }
+}
+
+fn effective_theme<R: Runtime>(app: &AppHandle<R>, appearance: &Appearance) -> Theme {
+    match appearance.theme {
+        ThemeMode::Light => Theme::Light,
+        ThemeMode::Dark => Theme::Dark,
+        ThemeMode::System => app
+            .get_webview_window("main")
+            .and_then(|window| window.theme().ok())
+            .unwrap_or(Theme::Light),
+    }
+}
+
+fn emit_theme_changed<R: Runtime>(
+    app: &AppHandle<R>,
+    appearance: &Appearance,
+    window_effect: WindowEffect,
+) {
+    let payload = ThemeChangedPayload {
+        theme: effective_theme(app, appearance),
+        mode: appearance.theme,
+        accent_color: appearance.accent_color.clone(),
+        compact: appearance.compact,
+        window_effect,
+    };
+    let _ = app.emit("theme-changed", payload);
+}
+
+// 按 tauri.conf.json 中 main 窗口的配置创建主窗口（配置中 create 为 false）
+fn create_main_window<R: Runtime>(app: &AppHandle<R>, transparent: bool) -> tauri::Result<()> {
+    let Some(config) = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|window| window.label == "main")
+    else {
+        println!("Appearance - Main window config not found");
+        return Ok(());
+    };
+    WebviewWindowBuilder::from_config(app, config)?
+        .transparent(transparent)
+        .build()?;
+    Ok(())
+}
+
+// 启动时创建主窗口并应用外观设置，然后监听系统主题变化
+pub fn setup_appearance<R: Runtime>(app: &AppHandle<R>) {
+    let appearance = current_settings(app).appearance;
+    let transparent = appearance.window_effect != WindowEffect::Off;
+    if let Err(e) = create_main_window(app, transparent) {
+        println!("Appearance - Failed to create main window: {:?}", e);
+    }
+    app.manage(AppearanceState {
+        active_effect: Mutex::new(WindowEffect::Off),
+        transparent,
+    });
+
+    apply_theme(app, &appearance);
+    let active_effect = apply_effect(app, &appearance);
+    *app.state::<AppearanceState>().active_effect.lock().unwrap() = active_effect;
+    reload_tray_icon(app, &appearance);
+
+    if let Some(main_window) = app.get_webview_window("main") {
+        let app_handle = app.clone();
+
+        main_window.on_window_event(move |event| {
+            if let WindowEvent::ThemeChanged(_) = event {
+                let state = app_handle.state::<AppearanceState>();
+                let appearance = current_settings(&app_handle).appearance;
+                // 托盘主题独立于窗口主题模式，系统主题变化时总是刷新托盘图标
+                reload_tray_icon(&app_handle, &appearance);
+                // 只有跟随系统时，系统主题变化才会影响实际主题
+                if appearance.theme == ThemeMode::System {
+                    let active_effect = apply_effect(&app_handle, &appearance);
+                    *state.active_effect.lock().unwrap() = active_effect;
+                    emit_theme_changed(&app_handle, &appearance, active_effect);
+                }
+            }
+        });
+    }
+}
+
+// 外观设置变化后重新应用到窗口和托盘，并通知前端
+pub fn apply_appearance<R: Runtime>(app: &AppHandle<R>, appearance: &Appearance) {
+    apply_theme(app, appearance);
+    let active_effect = apply_effect(app, appearance);
+    *app.state::<AppearanceState>().active_effect.lock().unwrap() = active_effect;
+    reload_tray_icon(app, appearance);
+    emit_theme_changed(app, appearance, active_effect);
+}
+
+#[tauri::command]
+pub fn get_appearance<R: Runtime>(app: AppHandle<R>) -> Appearance {
+    current_settings(&app).appearance
+}
+
+// 返回实际生效的窗口效果，前端据此决定是否绘制不透明背景
+#[tauri::command]
+pub fn get_active_window_effect(state: State<'_, AppearanceState>) -> WindowEffect {
+    *state.active_effect.lock().unwrap()
+}
+
+#[tauri::command]
+pub fn set_appearance<R: Runtime>(app: AppHandle<R>, appearance: Appearance) -> Result<(), String> {
+    update(&app, |settings| settings.appearance = appearance).map(|_| ())
+}
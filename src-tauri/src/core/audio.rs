@@ -0,0 +1,123 @@
+// core/audio.rs
+// 音频剪贴板：复制音频文件时记录时长，并支持在后台直接播放预览
+// clipboard-rs 不提供原始音频格式（如 Windows 的 CF_WAVE），因此只处理以文件形式复制的音频
+use rodio::{Decoder, OutputStream, Sink, Source};
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+use tauri::State;
+
+use crate::core::db::{get_item, Db, CONTENT_FILES};
+use crate::core::lock::AppLock;
+
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "flac", "ogg", "oga"];
+
+// 当前播放的停止标记，开始新的播放时先停止上一个
+#[derive(Default)]
+pub struct AudioPlayerState {
+    playing: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+fn open_decoder(path: &Path) -> Result<Decoder<BufReader<File>>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())
+}
+
+// 为文件列表中的音频文件生成元数据，列表中没有音频时返回 None
+// 部分格式不记录总帧数，此时 durationMs 为 null
+pub fn audio_metadata(files: &[String]) -> Option<Value> {
+    let audio: Vec<Value> = files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| is_audio_file(Path::new(file)))
+        .map(|(index, file)| {
+            let duration = open_decoder(Path::new(file))
+                .ok()
+                .and_then(|decoder| decoder.total_duration())
+                .map(|duration| duration.as_millis() as u64);
+            json!({ "index": index, "durationMs": duration })
+        })
+        .collect();
+
+    (!audio.is_empty()).then(|| json!({ "audio": audio }))
+}
+
+fn stop(state: &AudioPlayerState) {
+    if let Some(flag) = state.playing.lock().unwrap().take() {
+        flag.store(true, Ordering::SeqCst);
+    }
+}
+
+// 播放文件列表记录中的第一个音频文件
+#[tauri::command]
+pub fn play_entry(
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    player: State<'_, AudioPlayerState>,
+    id: i64,
+) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+    let item = get_item(&db.0.lock().unwrap(), id)
+        .map_err(|e| e.to_string())?
+        .ok_or("记录不存在")?;
+    if item.content_type != CONTENT_FILES {
+        return Err("该记录不是音频".into());
+    }
+
+    let files: Vec<String> = serde_json::from_str(&item.content).map_err(|e| e.to_string())?;
+    let path = files
+        .iter()
+        .map(Path::new)
+        .find(|path| is_audio_file(path))
+        .ok_or("该记录不是音频")?;
+    let decoder = open_decoder(path)?;
+
+    stop(&player);
+    let stopped = Arc::new(AtomicBool::new(false));
+    *player.playing.lock().unwrap() = Some(stopped.clone());
+
+    // 输出流不能跨线程传递，在播放线程中创建并一直持有到播放结束
+    thread::spawn(move || {
+        let (_stream, handle) = match OutputStream::try_default() {
+            Ok(output) => output,
+            Err(e) => {
+                println!("Audio - Failed to open output stream: {:?}", e);
+                return;
+            }
+        };
+        let sink = match Sink::try_new(&handle) {
+            Ok(sink) => sink,
+            Err(e) => {
+                println!("Audio - Failed to create sink: {:?}", e);
+                return;
+            }
+        };
+
+        sink.append(decoder);
+        while !sink.empty() && !stopped.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(50));
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_playback(player: State<'_, AudioPlayerState>) {
+    stop(&player);
+}
@@ -0,0 +1,695 @@
+// core/clipboard_watcher.rs
+// 剪贴板监听：在后台线程中轮询系统剪贴板（文件列表、文本和图片），发现新内容时写入历史并向前端发送 clipboard-changed 事件
+// 文本同时保存剪贴板中的 HTML、RTF 格式，轮询时只比较纯文本，发现新内容后才读取富文本
+// 系统通知剪贴板变化后才读取内容，空闲时不解码图片；无法接收通知时退回到每次轮询都读取
+use clipboard_rs::{
+    Clipboard, ClipboardContext, ClipboardHandler, ClipboardWatcher, ClipboardWatcherContext,
+    ContentFormat, RustImageData, WatcherShutdown,
+};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::core::animated::{read_animated, save_animated, AnimatedImage};
+use crate::core::anomaly::{report_anomaly, AnomalyDetector, AnomalySettings};
+use crate::core::audio::audio_metadata;
+use crate::core::db::{
+    content_hash, find_by_hash, insert_item, latest_item, rich_text, save_rich_text, touch_item,
+    ClipItem, Db, CONTENT_FILES, CONTENT_IMAGE, CONTENT_TEXT,
+};
+use crate::core::files::{read_files, write_files};
+use crate::core::images::{image_hash, save_image, write_image};
+use crate::core::link_preview::enrich_link;
+use crate::core::lock::AppLock;
+use crate::core::origin::origin_metadata;
+use crate::core::retention::{run_cleanup, schedule_expiry};
+use crate::core::rich_text::{
+    has_rich_text, mark_rich_text, read_rich_text, write_rich_text, RichText,
+};
+use crate::core::rules::apply_rules;
+use crate::core::screenshot::take_screenshot_metadata;
+use crate::core::sensitive::{
+    classify, conceal, contains_card, mark_concealed_sensitive, mark_secret, mask_clip,
+    password_hint, secret_providers, CardPolicy, SensitiveAction, SensitiveKind, SensitiveSettings,
+};
+use crate::core::settings::{current_settings, update};
+use crate::core::source_app::{foreground_app, SourceApp};
+use crate::core::svg::{has_svg, read_svg, svg_metadata, write_svg};
+use crate::core::transform::apply_transform_rules;
+use crate::core::tray::{refresh_tray_menu, update_tray_tooltip};
+
+// 轮询间隔的下限，避免设置过小时占满 CPU
+const MIN_POLL_INTERVAL_MS: u64 = 100;
+
+// 去重范围：只与最近一条比较，或在整个历史中查找相同内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupMode {
+    Consecutive,
+    #[default]
+    History,
+}
+
+// 来源应用过滤：记录所有应用，或只记录白名单中的应用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppFilterMode {
+    #[default]
+    All,
+    Allowlist,
+}
+
+// 默认排除的密码管理器
+const DEFAULT_EXCLUDED_APPS: &[&str] = &["KeePass", "KeePassXC", "1Password", "Bitwarden"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptureSettings {
+    pub dedup: DedupMode,
+    pub app_filter: AppFilterMode,
+    // 白名单中的应用名称或可执行文件名，不区分大小写
+    pub allowed_apps: Vec<String>,
+    // 排除的应用，来自这些应用的内容在任何模式下都不记录
+    pub excluded_apps: Vec<String>,
+    pub anomaly: AnomalySettings,
+    pub sensitive: SensitiveSettings,
+    // 是否保存文本的 HTML、RTF 格式
+    pub rich_text: bool,
+    // 粘贴文件列表中的图片时是否去除 EXIF 等元数据，粘贴时可以单独指定
+    pub strip_image_metadata: bool,
+    // 复制网址时是否联网获取网页标题和图标
+    pub link_previews: bool,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            dedup: DedupMode::default(),
+            app_filter: AppFilterMode::default(),
+            allowed_apps: Vec::new(),
+            excluded_apps: DEFAULT_EXCLUDED_APPS
+                .iter()
+                .map(|app| app.to_string())
+                .collect(),
+            anomaly: AnomalySettings::default(),
+            sensitive: SensitiveSettings::default(),
+            rich_text: true,
+            strip_image_metadata: false,
+            link_previews: true,
+        }
+    }
+}
+
+impl CaptureSettings {
+    // 是否记录来自该应用的内容；白名单模式下无法识别来源应用时不记录
+    fn allows(&self, source: Option<&SourceApp>) -> bool {
+        if source.is_some_and(|source| self.excluded_apps.iter().any(|app| source.matches(app))) {
+            return false;
+        }
+        match self.app_filter {
+            AppFilterMode::All => true,
+            AppFilterMode::Allowlist => {
+                source.is_some_and(|source| self.allowed_apps.iter().any(|app| source.matches(app)))
+            }
+        }
+    }
+}
+
+// 保存当前监听线程的运行标记（None 表示监听已停止）和异常检测的统计
+#[derive(Default)]
+pub struct ClipboardWatcherState {
+    running: Mutex<Option<Arc<AtomicBool>>>,
+    anomaly: AnomalyDetector,
+}
+
+// 一次读取到的剪贴板内容
+enum Clip {
+    // 文件路径列表，content 为序列化后的 JSON 数组
+    Files { paths: Vec<String>, content: String },
+    Text(String),
+    Image(RustImageData),
+    Animated(AnimatedImage),
+}
+
+// 按 文件列表 > 文本 > 图片 的优先级读取剪贴板，并返回内容哈希
+// 复制文件时文件管理器通常也会写入路径文本，复制文档内容时可能同时带有图片，这两种情况只记录前者
+fn read_clip(ctx: &ClipboardContext) -> Option<(Clip, String)> {
+    if let Some(files) = read_files(ctx) {
+        let content = serde_json::to_string(&files).ok()?;
+        let hash = content_hash(content.as_bytes());
+        return Some((
+            Clip::Files {
+                paths: files,
+                content,
+            },
+            hash,
+        ));
+    }
+
+    // 设计工具复制的矢量图通常同时带有 PNG，按 SVG 源码保存
+    if let Some(source) = read_svg(ctx) {
+        let hash = content_hash(source.as_bytes());
+        return Some((Clip::Text(source), hash));
+    }
+
+    if let Some(text) = ctx.get_text().ok().filter(|text| !text.is_empty()) {
+        let hash = content_hash(text.as_bytes());
+        return Some((Clip::Text(text), hash));
+    }
+
+    // 动图按原始数据计算哈希，解码后只能得到第一帧
+    if let Some(animated) = read_animated(ctx) {
+        let hash = content_hash(&animated.data);
+        return Some((Clip::Animated(animated), hash));
+    }
+
+    if ctx.has(ContentFormat::Image) {
+        let image = ctx.get_image().ok()?;
+        let hash = image_hash(&image)?;
+        return Some((Clip::Image(image), hash));
+    }
+
+    None
+}
+
+// 按去重范围查找与新内容相同的记录
+fn find_duplicate(
+    conn: &Connection,
+    hash: &str,
+    mode: DedupMode,
+) -> rusqlite::Result<Option<ClipItem>> {
+    match mode {
+        DedupMode::Consecutive => Ok(latest_item(conn)?.filter(|item| item.hash == hash)),
+        DedupMode::History => find_by_hash(conn, hash),
+    }
+}
+
+// 重复的内容只更新已有记录的 last_used，事件负载为更新后的记录，前端按 id 合并
+// rich 为新记录的富文本格式，重复的内容保留原记录的格式；规则也只对新记录执行
+fn store_and_emit<R: Runtime>(
+    app: &AppHandle<R>,
+    content: &str,
+    content_type: &str,
+    hash: &str,
+    metadata: Option<Value>,
+    expires_at: Option<i64>,
+    rich: Option<&RichText>,
+) -> Option<ClipItem> {
+    let mode = current_settings(app).capture.dedup;
+    let db = app.state::<Db>();
+    let stored = {
+        let conn = db.0.lock().unwrap();
+        match find_duplicate(&conn, hash, mode) {
+            Ok(Some(item)) => touch_item(&conn, item.id).map(|item| (item, false)),
+            Ok(None) => insert_item(&conn, content, content_type, hash, metadata, expires_at)
+                .and_then(|item| match rich {
+                    Some(rich) => save_rich_text(&conn, item.id, rich).map(|_| item),
+                    None => Ok(item),
+                })
+                .map(|item| (item, true)),
+            Err(e) => Err(e),
+        }
+    };
+
+    match stored {
+        Ok((item, inserted)) => {
+            if inserted {
+                apply_rules(app, &item);
+            }
+            // 锁定期间不向前端推送内容，解锁后前端重新加载历史
+            if !app.state::<AppLock>().is_locked() {
+                let mut payload = item.clone();
+                conceal(&mut payload, &current_settings(app).capture.sensitive);
+                if let Err(e) = app.emit("clipboard-changed", payload) {
+                    println!("Clipboard watcher - Failed to emit event: {:?}", e);
+                }
+            }
+            if let Err(e) = run_cleanup(app) {
+                println!("Clipboard watcher - Cleanup failed: {}", e);
+            }
+            refresh_tray_menu(app);
+            Some(item)
+        }
+        Err(e) => {
+            println!("Clipboard watcher - Failed to save item: {:?}", e);
+            None
+        }
+    }
+}
+
+// 文本先做敏感内容识别：密码等按设置隐藏、遮盖保存或不保存，含有密钥的文本原样保存但标记并设置到期时间
+// 富文本中同样含有敏感内容，只有未被识别为敏感的文本才保存富文本格式
+// password_hint 为剪贴板中是否有密码管理器的标记
+fn capture_text<R: Runtime>(
+    app: &AppHandle<R>,
+    text: &str,
+    hash: &str,
+    metadata: Option<Value>,
+    sensitive: &SensitiveSettings,
+    rich: Option<RichText>,
+    password_hint: bool,
+) -> Option<ClipItem> {
+    let store_plain = |metadata: Option<Value>| {
+        let rich = rich.as_ref().filter(|rich| !rich.is_empty());
+        let metadata = match rich {
+            Some(rich) => Some(mark_rich_text(metadata, rich)),
+            None => metadata,
+        };
+        store_and_emit(app, text, CONTENT_TEXT, hash, metadata, None, rich)
+    };
+    // 原文按密钥保存：列表中隐藏，到期删除
+    let store_secret = |metadata: Value| {
+        let expires_at = sensitive.secret_expires_at();
+        let item = store_and_emit(
+            app,
+            text,
+            CONTENT_TEXT,
+            hash,
+            Some(metadata),
+            expires_at,
+            None,
+        );
+        if let Some(expires_at) = expires_at {
+            schedule_expiry(expires_at);
+        }
+        item
+    };
+
+    let card = (sensitive.card_policy != CardPolicy::Off && contains_card(text))
+        .then_some(SensitiveKind::CreditCard);
+    if card.is_none() && !sensitive.enabled {
+        return store_plain(metadata);
+    }
+
+    match card.or_else(|| classify(text, password_hint)) {
+        Some(SensitiveKind::CreditCard) if sensitive.card_policy == CardPolicy::Block => {
+            println!("Clipboard watcher - Blocked text containing a card number");
+            None
+        }
+        Some(kind)
+            if kind != SensitiveKind::CreditCard && sensitive.action == SensitiveAction::Skip =>
+        {
+            println!("Clipboard watcher - Skipped sensitive text: {:?}", kind);
+            None
+        }
+        Some(kind)
+            if kind != SensitiveKind::CreditCard
+                && sensitive.action == SensitiveAction::Conceal =>
+        {
+            store_secret(mark_concealed_sensitive(metadata, kind))
+        }
+        Some(kind) => {
+            let masked = mask_clip(text, kind, metadata, sensitive);
+            let item = store_and_emit(
+                app,
+                &masked.content,
+                CONTENT_TEXT,
+                &masked.hash,
+                Some(masked.metadata),
+                masked.expires_at,
+                None,
+            );
+            if let Some(expires_at) = masked.expires_at {
+                schedule_expiry(expires_at);
+            }
+            item
+        }
+        None => {
+            let providers = secret_providers(text);
+            if providers.is_empty() {
+                return store_plain(metadata);
+            }
+            store_secret(mark_secret(metadata, &providers))
+        }
+    }
+}
+
+// 保存剪贴板以外来源的文本（例如监视文件夹中的文件），同样经过敏感内容识别，返回保存的记录
+pub fn ingest_text<R: Runtime>(
+    app: &AppHandle<R>,
+    text: &str,
+    metadata: Option<Value>,
+) -> Option<ClipItem> {
+    let hash = content_hash(text.as_bytes());
+    let sensitive = current_settings(app).capture.sensitive;
+    capture_text(app, text, &hash, metadata, &sensitive, None, false)
+}
+
+// 保存剪贴板以外来源的图片，返回保存的记录
+pub fn ingest_image<R: Runtime>(
+    app: &AppHandle<R>,
+    image: &RustImageData,
+    metadata: Option<Value>,
+) -> Result<Option<ClipItem>, String> {
+    let hash = image_hash(image).ok_or("无法读取图片")?;
+    let file_name = save_image(app, image, &hash)?;
+    Ok(store_and_emit(
+        app,
+        &file_name,
+        CONTENT_IMAGE,
+        &hash,
+        metadata,
+        None,
+        None,
+    ))
+}
+
+fn capture<R: Runtime>(app: &AppHandle<R>, ctx: &ClipboardContext, clip: Clip, hash: &str) {
+    let state = app.state::<ClipboardWatcherState>();
+    let current = current_settings(app);
+    let settings = current.capture;
+
+    // 异常检测统计所有应用的复制，不受来源过滤影响
+    let text = match &clip {
+        Clip::Text(text) => Some(text.as_str()),
+        _ => None,
+    };
+    if let Some(anomaly) = state.anomaly.record(text, &settings.anomaly) {
+        report_anomaly(app, &anomaly);
+        if anomaly.paused {
+            stop(&state);
+            refresh_tray_menu(app);
+            return;
+        }
+    }
+
+    let source = foreground_app();
+    if !settings.allows(source.as_ref()) {
+        return;
+    }
+
+    match clip {
+        Clip::Files { paths, content } => {
+            let metadata = audio_metadata(&paths);
+            store_and_emit(app, &content, CONTENT_FILES, hash, metadata, None, None);
+        }
+        Clip::Text(text) => {
+            let metadata = source.as_ref().and_then(origin_metadata);
+            let (text, hash, metadata, rich) =
+                match apply_transform_rules(&text, &current.transforms) {
+                    // 转换后的文本与富文本格式不再一致，只保存纯文本
+                    Some((transformed, applied)) => {
+                        if transformed.trim().is_empty() {
+                            return;
+                        }
+                        let mut metadata = metadata.unwrap_or_else(|| json!({}));
+                        metadata["transformedBy"] = json!(applied);
+                        let hash = content_hash(transformed.as_bytes());
+                        (transformed, hash, Some(metadata), None)
+                    }
+                    None => {
+                        let rich = settings.rich_text.then(|| read_rich_text(ctx));
+                        (text, hash.to_string(), metadata, rich)
+                    }
+                };
+            // SVG 源码同时渲染预览图
+            let metadata = svg_metadata(app, &text, &hash, metadata);
+            let hint = password_hint(ctx);
+            if let Some(item) =
+                capture_text(app, &text, &hash, metadata, &settings.sensitive, rich, hint)
+            {
+                enrich_link(app, &item);
+            }
+        }
+        Clip::Image(image) => match save_image(app, &image, hash) {
+            Ok(file_name) => {
+                let metadata =
+                    take_screenshot_metadata(current.hotkeys.print_screen, source.as_ref());
+                store_and_emit(app, &file_name, CONTENT_IMAGE, hash, metadata, None, None);
+            }
+            Err(e) => println!("Clipboard watcher - Failed to save image: {:?}", e),
+        },
+        Clip::Animated(animated) => match save_animated(app, &animated, hash) {
+            Ok((file_name, metadata)) => {
+                store_and_emit(
+                    app,
+                    &file_name,
+                    CONTENT_IMAGE,
+                    hash,
+                    Some(metadata),
+                    None,
+                    None,
+                );
+            }
+            Err(e) => println!("Clipboard watcher - Failed to save animation: {:?}", e),
+        },
+    }
+}
+
+// 将一条历史记录按原来的类型写回系统剪贴板，文本同时写入保存的富文本格式
+// 调用时不能持有数据库锁
+pub fn write_item<R: Runtime>(
+    app: &AppHandle<R>,
+    item: &ClipItem,
+    strip_metadata: Option<bool>,
+) -> Result<(), String> {
+    let ctx = ClipboardContext::new().map_err(|e| e.to_string())?;
+    match item.content_type.as_str() {
+        CONTENT_TEXT if has_svg(item.metadata.as_ref()) => write_svg(app, &ctx, item),
+        CONTENT_TEXT if has_rich_text(item.metadata.as_ref()) => {
+            let rich = rich_text(&app.state::<Db>().0.lock().unwrap(), item.id)
+                .map_err(|e| e.to_string())?;
+            write_rich_text(&ctx, &item.content, rich)
+        }
+        CONTENT_TEXT => ctx
+            .set_text(item.content.clone())
+            .map_err(|e| e.to_string()),
+        CONTENT_IMAGE => write_image(app, &ctx, item),
+        CONTENT_FILES => write_files(app, &ctx, &item.content, strip_metadata),
+        other => Err(format!("不支持的记录类型: {}", other)),
+    }
+}
+
+// 把生成的文本（整理后的 Issue 正文、脱敏副本等）写入剪贴板，监听线程会把它作为新记录保存
+pub fn write_text(text: &str) -> Result<(), String> {
+    let ctx = ClipboardContext::new().map_err(|e| e.to_string())?;
+    ctx.set_text(text.to_string()).map_err(|e| e.to_string())
+}
+
+// 以 --restore-last 启动时，把最近一条历史写回剪贴板，找回重启前丢失的剪贴板内容
+// 需要在监听线程启动前执行，这样恢复的内容会作为监听的基准，不会再次写入历史
+pub fn restore_last_clip<R: Runtime>(app: &AppHandle<R>) {
+    let db = app.state::<Db>();
+    let latest = latest_item(&db.0.lock().unwrap());
+
+    let restored = match latest {
+        Ok(Some(item)) => write_item(app, &item, None),
+        Ok(None) => return,
+        Err(e) => Err(e.to_string()),
+    };
+    match restored {
+        Ok(()) => println!("Clipboard - Restored last clip"),
+        Err(e) => println!("Clipboard - Failed to restore last clip: {}", e),
+    }
+}
+
+// 剪贴板变化时由 clipboard-rs 的监听器置位
+struct ChangeFlag(Arc<AtomicBool>);
+
+impl ClipboardHandler for ChangeFlag {
+    fn on_clipboard_change(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+// 在单独的线程中接收系统的剪贴板变化通知（Windows 的消息窗口需要在监听线程中创建）
+// 创建失败时返回 None，监听线程异常退出时 JoinHandle 会结束，两种情况都退回到每次都读取
+fn spawn_change_listener(changed: Arc<AtomicBool>) -> (Option<WatcherShutdown>, JoinHandle<()>) {
+    let (tx, rx) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        let mut watcher = match ClipboardWatcherContext::new() {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                println!("Clipboard watcher - Failed to listen for changes: {:?}", e);
+                return;
+            }
+        };
+        watcher.add_handler(ChangeFlag(changed));
+        let _ = tx.send(watcher.get_shutdown_channel());
+        watcher.start_watch();
+    });
+    (rx.recv().ok(), handle)
+}
+
+fn watch_loop<R: Runtime>(app: AppHandle<R>, running: Arc<AtomicBool>) {
+    let ctx = match ClipboardContext::new() {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            println!("Clipboard watcher - Failed to open clipboard: {:?}", e);
+            running.store(false, Ordering::SeqCst);
+            return;
+        }
+    };
+
+    let changed = Arc::new(AtomicBool::new(false));
+    let (shutdown, listener) = spawn_change_listener(changed.clone());
+
+    // 以启动时的剪贴板内容为基准，只上报之后的新内容
+    let mut last_hash = read_clip(&ctx).map(|(_, hash)| hash);
+
+    while running.load(Ordering::SeqCst) {
+        let interval = current_settings(&app)
+            .poll_interval_ms
+            .max(MIN_POLL_INTERVAL_MS);
+        thread::sleep(Duration::from_millis(interval));
+
+        if !changed.swap(false, Ordering::SeqCst) && !listener.is_finished() {
+            continue;
+        }
+        let Some((clip, hash)) = read_clip(&ctx) else {
+            // 剪贴板可能正被其他程序占用，下次轮询再读
+            changed.store(true, Ordering::SeqCst);
+            continue;
+        };
+        if last_hash.as_deref() == Some(hash.as_str()) {
+            continue;
+        }
+
+        capture(&app, &ctx, clip, &hash);
+        last_hash = Some(hash);
+    }
+
+    if let Some(shutdown) = shutdown {
+        shutdown.stop();
+    }
+    println!("Clipboard watcher stopped");
+}
+
+fn is_running(running: &Option<Arc<AtomicBool>>) -> bool {
+    running
+        .as_ref()
+        .is_some_and(|flag| flag.load(Ordering::SeqCst))
+}
+
+fn start<R: Runtime>(app: &AppHandle<R>, state: &ClipboardWatcherState) -> bool {
+    let mut running = state.running.lock().unwrap();
+    if is_running(&running) {
+        return false;
+    }
+
+    // 每个监听线程持有自己的运行标记，避免停止后立即重启时出现两个线程
+    let flag = Arc::new(AtomicBool::new(true));
+    *running = Some(flag.clone());
+
+    let app_handle = app.clone();
+    thread::spawn(move || watch_loop(app_handle, flag));
+    println!("Clipboard watcher started");
+    true
+}
+
+fn stop(state: &ClipboardWatcherState) -> bool {
+    match state.running.lock().unwrap().take() {
+        Some(flag) => {
+            flag.store(false, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+// 供应用锁暂停和恢复监听，返回状态是否发生变化
+pub fn pause_watcher<R: Runtime>(app: &AppHandle<R>) -> bool {
+    stop(&app.state::<ClipboardWatcherState>())
+}
+
+pub fn resume_watcher<R: Runtime>(app: &AppHandle<R>) -> bool {
+    start(app, &app.state::<ClipboardWatcherState>())
+}
+
+pub fn is_watcher_running<R: Runtime>(app: &AppHandle<R>) -> bool {
+    is_running(&app.state::<ClipboardWatcherState>().running.lock().unwrap())
+}
+
+// 采集设置变化后更新托盘提示，其余设置在每次采集时读取
+pub fn apply_capture_settings<R: Runtime>(app: &AppHandle<R>, settings: &CaptureSettings) {
+    update_tray_tooltip(app, settings.app_filter == AppFilterMode::Allowlist);
+}
+
+// 应用启动时开启剪贴板监听
+pub fn setup_clipboard_watcher<R: Runtime>(app: &AppHandle<R>) {
+    apply_capture_settings(app, &current_settings(app).capture);
+    start(app, &app.state::<ClipboardWatcherState>());
+}
+
+// 锁定期间不能恢复监听，解锁时由 lock 模块恢复锁定前的状态
+#[tauri::command]
+pub fn start_clipboard_watcher<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, ClipboardWatcherState>,
+    lock: State<'_, AppLock>,
+) -> Result<bool, String> {
+    lock.ensure_unlocked()?;
+    let changed = start(&app, &state);
+    refresh_tray_menu(&app);
+    Ok(changed)
+}
+
+#[tauri::command]
+pub fn stop_clipboard_watcher<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, ClipboardWatcherState>,
+) -> bool {
+    let changed = stop(&state);
+    refresh_tray_menu(&app);
+    changed
+}
+
+#[tauri::command]
+pub fn is_clipboard_watcher_running(state: State<'_, ClipboardWatcherState>) -> bool {
+    is_running(&state.running.lock().unwrap())
+}
+
+#[tauri::command]
+pub fn get_capture_settings<R: Runtime>(app: AppHandle<R>) -> CaptureSettings {
+    current_settings(&app).capture
+}
+
+#[tauri::command]
+pub fn set_capture_settings<R: Runtime>(
+    app: AppHandle<R>,
+    settings: CaptureSettings,
+) -> Result<(), String> {
+    update(&app, |current| current.capture = settings).map(|_| ())
+}
+
+// 添加排除的应用，已存在时不重复添加，返回修改后的排除列表
+#[tauri::command]
+pub fn add_excluded_app<R: Runtime>(
+    app: AppHandle<R>,
+    name: String,
+) -> Result<Vec<String>, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("应用名称不能为空".into());
+    }
+    let settings = update(&app, |settings| {
+        let excluded = &mut settings.capture.excluded_apps;
+        if !excluded.iter().any(|app| app.eq_ignore_ascii_case(&name)) {
+            excluded.push(name);
+        }
+    })?;
+    Ok(settings.capture.excluded_apps)
+}
+
+#[tauri::command]
+pub fn remove_excluded_app<R: Runtime>(
+    app: AppHandle<R>,
+    name: String,
+) -> Result<Vec<String>, String> {
+    let settings = update(&app, |settings| {
+        settings
+            .capture
+            .excluded_apps
+            .retain(|app| !app.eq_ignore_ascii_case(name.trim()))
+    })?;
+    Ok(settings.capture.excluded_apps)
+}
@@ -0,0 +1,849 @@
+// core/db.rs
+// 剪贴板历史存储：SQLite 数据库，启动时建表并按顺序执行迁移
+// 可选用 SQLCipher 整库加密，密钥保存在系统钥匙串中；图片文件保存在数据库之外，不在加密范围内
+// 本月之前的记录归档到按月分片（见 shards.rs），历史分页、统计等查询合并主数据库和分片的结果
+use rusqlite::{params, Connection, DatabaseName, OptionalExtension, Row};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::core::hotkeys::{unbind_target, HotkeyTarget};
+use crate::core::images::{remove_image_file, remove_unreferenced_images};
+use crate::core::keystore::{delete_secret, get_secret, set_secret, Secret};
+use crate::core::lock::AppLock;
+use crate::core::previews::clear_previews;
+use crate::core::rich_text::RichText;
+use crate::core::search::SEARCH_SQL;
+use crate::core::sensitive::conceal;
+use crate::core::settings::current_settings;
+use crate::core::shards::{list_shards, query_shards, remove_shard, restore_item, with_shard};
+use crate::core::tray::refresh_tray_menu;
+
+const DB_FILE: &str = "clipper.db";
+// 数据库密钥为 32 字节随机数，保存在系统钥匙串中
+const DB_KEY_LEN: usize = 32;
+
+// items.content_type 的取值；图片记录的 content 为 images 目录下的文件名，
+// 文件列表记录的 content 为路径的 JSON 数组
+pub const CONTENT_TEXT: &str = "text";
+pub const CONTENT_IMAGE: &str = "image";
+pub const CONTENT_FILES: &str = "files";
+
+// 预编译语句缓存容量，需要大于热点查询的数量
+const STATEMENT_CACHE_CAPACITY: usize = 32;
+
+// 拼接查询完整记录的 SELECT 语句，列与 ClipItem::from_row 对应，标签以 JSON 数组返回
+// 带 "shard." 前缀时查询附加的分片
+macro_rules! select_items {
+    ($rest:literal) => {
+        select_items!("", $rest)
+    };
+    ($schema:literal, $rest:literal) => {
+        concat!(
+            "SELECT id, content, content_type, created_at, last_used, hash, metadata, pinned, expires_at,
+                (SELECT json_group_array(tag) FROM ",
+            $schema,
+            "item_tags WHERE item_id = items.id) AS tags
+             FROM ",
+            $schema,
+            "items AS items ",
+            $rest
+        )
+    };
+}
+
+const INSERT_ITEM_SQL: &str =
+    "INSERT INTO items (content, content_type, created_at, last_used, hash, metadata, expires_at)
+     VALUES (?1, ?2, ?3, ?3, ?4, ?5, ?6)";
+const TOUCH_ITEM_SQL: &str = "UPDATE items SET last_used = ?1 WHERE id = ?2";
+const ITEM_BY_ID_SQL: &str = select_items!("WHERE id = ?1");
+const ITEM_BY_HASH_SQL: &str =
+    select_items!("WHERE hash = ?1 ORDER BY last_used DESC, id DESC LIMIT 1");
+const HISTORY_PAGE_SQL: &str = select_items!("ORDER BY last_used DESC, id DESC LIMIT ?1 OFFSET ?2");
+const PINNED_SQL: &str = select_items!("WHERE pinned = 1 ORDER BY last_used DESC, id DESC");
+const SHARD_PAGE_SQL: &str = select_items!("shard.", "ORDER BY last_used DESC, id DESC LIMIT ?1");
+
+// 热点查询，新增的高频查询需要登记在这里，以便通过 get_query_plans 检查执行计划
+const HOT_QUERIES: &[(&str, &str)] = &[
+    ("insert_item", INSERT_ITEM_SQL),
+    ("touch_item", TOUCH_ITEM_SQL),
+    ("item_by_hash", ITEM_BY_HASH_SQL),
+    ("search", SEARCH_SQL),
+    ("history_page", HISTORY_PAGE_SQL),
+    ("pinned", PINNED_SQL),
+];
+
+// 迁移脚本按顺序执行，数据库的 user_version 记录已经执行到第几个
+// 已发布的迁移不能修改，结构变化只能追加新的迁移
+const MIGRATIONS: &[&str] = &[
+    // 1: 历史记录表
+    "CREATE TABLE items (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        content TEXT NOT NULL,
+        content_type TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        hash TEXT NOT NULL
+    );
+    CREATE INDEX idx_items_created_at ON items(created_at);
+    CREATE INDEX idx_items_hash ON items(hash);",
+    // 2: 附加元数据（JSON），例如音频时长
+    "ALTER TABLE items ADD COLUMN metadata TEXT;",
+    // 3: 最近使用时间，重复复制时只更新它，历史按它排序
+    "ALTER TABLE items ADD COLUMN last_used INTEGER NOT NULL DEFAULT 0;
+    UPDATE items SET last_used = created_at;
+    CREATE INDEX idx_items_last_used ON items(last_used);",
+    // 4: 全文索引，外部内容表由触发器维护，不索引图片记录
+    "CREATE VIRTUAL TABLE items_fts USING fts5(
+        content,
+        content = 'items',
+        content_rowid = 'id',
+        prefix = '2 3'
+    );
+    CREATE TRIGGER items_fts_insert AFTER INSERT ON items
+    WHEN new.content_type != 'image' BEGIN
+        INSERT INTO items_fts (rowid, content) VALUES (new.id, new.content);
+    END;
+    CREATE TRIGGER items_fts_delete AFTER DELETE ON items
+    WHEN old.content_type != 'image' BEGIN
+        INSERT INTO items_fts (items_fts, rowid, content) VALUES ('delete', old.id, old.content);
+    END;
+    INSERT INTO items_fts (rowid, content)
+    SELECT id, content FROM items WHERE content_type != 'image';",
+    // 5: 固定的记录不参与历史清理
+    "ALTER TABLE items ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+    CREATE INDEX idx_items_pinned ON items(pinned) WHERE pinned = 1;",
+    // 6: 到期时间，敏感内容到期后由清理任务删除
+    "ALTER TABLE items ADD COLUMN expires_at INTEGER;
+    CREATE INDEX idx_items_expires_at ON items(expires_at) WHERE expires_at IS NOT NULL;",
+    // 7: 文本记录的富文本格式，只在写回剪贴板时读取，不随列表查询返回
+    "ALTER TABLE items ADD COLUMN html TEXT;
+    ALTER TABLE items ADD COLUMN rtf TEXT;",
+    // 8: 标签，删除记录时由触发器删除它的标签
+    "CREATE TABLE item_tags (
+        item_id INTEGER NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (item_id, tag)
+    );
+    CREATE INDEX idx_item_tags_tag ON item_tags(tag);
+    CREATE TRIGGER items_tags_delete AFTER DELETE ON items BEGIN
+        DELETE FROM item_tags WHERE item_id = old.id;
+    END;",
+    // 9: 片段库，与历史记录分开保存，不参与清理和搜索
+    "CREATE TABLE snippets (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        content TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+    );",
+];
+
+// 作为 Tauri 托管状态共享的数据库连接
+pub struct Db(pub Mutex<Connection>);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryPlan {
+    pub name: String,
+    pub sql: String,
+    // EXPLAIN QUERY PLAN 输出的 detail 列，按层级缩进
+    pub plan: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipItem {
+    pub id: i64,
+    pub content: String,
+    pub content_type: String,
+    // Unix 时间戳（毫秒）
+    pub created_at: i64,
+    // 最近一次复制的时间（毫秒），去重时会被更新
+    pub last_used: i64,
+    pub hash: String,
+    pub metadata: Option<Value>,
+    // 固定的记录单独展示，不会被清理
+    pub pinned: bool,
+    // 到期时间（毫秒），到期后自动删除
+    pub expires_at: Option<i64>,
+    pub tags: Vec<String>,
+}
+
+impl ClipItem {
+    pub fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            content: row.get("content")?,
+            content_type: row.get("content_type")?,
+            created_at: row.get("created_at")?,
+            last_used: row.get("last_used")?,
+            hash: row.get("hash")?,
+            metadata: row.get("metadata")?,
+            pinned: row.get("pinned")?,
+            expires_at: row.get("expires_at")?,
+            tags: serde_json::from_str(&row.get::<_, String>("tags")?).unwrap_or_default(),
+        })
+    }
+}
+
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+pub fn content_hash(content: &[u8]) -> String {
+    blake3::hash(content).to_hex().to_string()
+}
+
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", index as i64 + 1)?;
+        tx.commit()?;
+        println!("Database migrated to version {}", index + 1);
+    }
+
+    Ok(())
+}
+
+// 测试用的内存数据库，已执行全部迁移
+#[cfg(test)]
+pub fn open_in_memory() -> Connection {
+    let mut conn = Connection::open_in_memory().unwrap();
+    migrate(&mut conn).unwrap();
+    conn
+}
+
+fn db_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(DB_FILE))
+}
+
+// SQLCipher 原始密钥的写法，跳过口令派生
+fn raw_key(key: &str) -> String {
+    format!("x'{}'", key)
+}
+
+// 打开数据库并执行迁移，key 为十六进制的数据库密钥，None 表示数据库未加密
+pub fn connect(path: &Path, key: Option<&str>) -> rusqlite::Result<Connection> {
+    let mut conn = Connection::open(path)?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", raw_key(key))?;
+    }
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    migrate(&mut conn)?;
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+    // 让 SQLite 按需更新统计信息，保持查询计划准确
+    conn.execute_batch("PRAGMA optimize;")?;
+    Ok(conn)
+}
+
+// 钥匙串中保存了数据库密钥即表示数据库已加密；读取钥匙串失败时按未加密处理
+pub fn stored_key() -> Option<String> {
+    get_secret(Secret::DatabaseKey).unwrap_or_else(|e| {
+        println!("Database - Failed to read key from keystore: {}", e);
+        None
+    })
+}
+
+// 打开应用数据目录下的数据库并执行迁移
+pub fn open_db<R: Runtime>(app: &AppHandle<R>) -> Result<Db, String> {
+    let conn = connect(&db_path(app)?, stored_key().as_deref()).map_err(|e| e.to_string())?;
+    Ok(Db(Mutex::new(conn)))
+}
+
+// 定时维护：把 WAL 日志合并回数据库并截断，更新查询统计
+pub fn maintain(conn: &Connection) -> rusqlite::Result<()> {
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    conn.execute_batch("PRAGMA optimize;")
+}
+
+// hash 由调用方计算：文本为内容的哈希，图片为像素数据的哈希
+pub fn insert_item(
+    conn: &Connection,
+    content: &str,
+    content_type: &str,
+    hash: &str,
+    metadata: Option<Value>,
+    expires_at: Option<i64>,
+) -> rusqlite::Result<ClipItem> {
+    let created_at = now_millis();
+    conn.prepare_cached(INSERT_ITEM_SQL)?.execute(params![
+        content,
+        content_type,
+        created_at,
+        hash,
+        metadata,
+        expires_at
+    ])?;
+
+    Ok(ClipItem {
+        id: conn.last_insert_rowid(),
+        content: content.to_string(),
+        content_type: content_type.to_string(),
+        created_at,
+        last_used: created_at,
+        hash: hash.to_string(),
+        metadata,
+        pinned: false,
+        expires_at,
+        tags: Vec::new(),
+    })
+}
+
+pub fn save_rich_text(conn: &Connection, id: i64, rich: &RichText) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE items SET html = ?1, rtf = ?2 WHERE id = ?3",
+        params![rich.html, rich.rtf, id],
+    )?;
+    Ok(())
+}
+
+// 设置元数据中的一个字段，其他字段保持不变
+pub fn update_metadata(
+    conn: &Connection,
+    id: i64,
+    key: &str,
+    value: &Value,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE items SET metadata = json_set(COALESCE(metadata, '{}'), ?1, json(?2)) WHERE id = ?3",
+        params![format!("$.{}", key), value.to_string(), id],
+    )?;
+    Ok(())
+}
+
+// 记录不存在时返回空的富文本
+pub fn rich_text(conn: &Connection, id: i64) -> rusqlite::Result<RichText> {
+    conn.query_row(
+        "SELECT html, rtf FROM items WHERE id = ?1",
+        params![id],
+        |row| {
+            Ok(RichText {
+                html: row.get("html")?,
+                rtf: row.get("rtf")?,
+            })
+        },
+    )
+    .optional()
+    .map(Option::unwrap_or_default)
+}
+
+// 记录已归档时先移回主数据库
+pub fn get_item(conn: &Connection, id: i64) -> rusqlite::Result<Option<ClipItem>> {
+    let item = conn
+        .prepare_cached(ITEM_BY_ID_SQL)?
+        .query_row(params![id], ClipItem::from_row)
+        .optional()?;
+    if item.is_some() || !restore_item(conn, id)? {
+        return Ok(item);
+    }
+    conn.prepare_cached(ITEM_BY_ID_SQL)?
+        .query_row(params![id], ClipItem::from_row)
+        .optional()
+}
+
+// 重复复制时更新记录的最近使用时间，返回更新后的记录
+pub fn touch_item(conn: &Connection, id: i64) -> rusqlite::Result<ClipItem> {
+    conn.prepare_cached(TOUCH_ITEM_SQL)?
+        .execute(params![now_millis(), id])?;
+    conn.prepare_cached(ITEM_BY_ID_SQL)?
+        .query_row(params![id], ClipItem::from_row)
+}
+
+// 内容哈希相同的记录中最近使用的一条
+// 主数据库中没有时按月份从新到旧查找分片，找到的记录先移回主数据库
+pub fn find_by_hash(conn: &Connection, hash: &str) -> rusqlite::Result<Option<ClipItem>> {
+    let item = conn
+        .prepare_cached(ITEM_BY_HASH_SQL)?
+        .query_row(params![hash], ClipItem::from_row)
+        .optional()?;
+    if item.is_some() {
+        return Ok(item);
+    }
+    for shard in list_shards(conn) {
+        let id: Option<i64> = with_shard(conn, &shard, || {
+            conn.query_row(
+                "SELECT id FROM shard.items WHERE hash = ?1 ORDER BY last_used DESC, id DESC LIMIT 1",
+                params![hash],
+                |row| row.get(0),
+            )
+            .optional()
+        })?;
+        if let Some(id) = id {
+            restore_item(conn, id)?;
+            return conn
+                .prepare_cached(ITEM_BY_ID_SQL)?
+                .query_row(params![id], ClipItem::from_row)
+                .optional();
+        }
+    }
+    Ok(None)
+}
+
+// 最近的一条历史记录
+pub fn latest_item(conn: &Connection) -> rusqlite::Result<Option<ClipItem>> {
+    conn.prepare_cached(HISTORY_PAGE_SQL)?
+        .query_row(params![1, 0], ClipItem::from_row)
+        .optional()
+}
+
+fn by_last_used(a: &ClipItem, b: &ClipItem) -> std::cmp::Ordering {
+    b.last_used.cmp(&a.last_used).then(b.id.cmp(&a.id))
+}
+
+// 按最近使用时间倒序分页读取历史
+// 有分片时每处取前 offset + limit 条合并，分片按月份从新到旧，已经取够更新的记录后不再查询
+pub fn history_page(conn: &Connection, limit: u32, offset: u32) -> rusqlite::Result<Vec<ClipItem>> {
+    let shards = list_shards(conn);
+    if shards.is_empty() {
+        return conn
+            .prepare_cached(HISTORY_PAGE_SQL)?
+            .query_map(params![limit, offset], ClipItem::from_row)?
+            .collect();
+    }
+
+    let wanted = (offset + limit) as usize;
+    let mut items: Vec<ClipItem> = conn
+        .prepare_cached(HISTORY_PAGE_SQL)?
+        .query_map(params![wanted, 0], ClipItem::from_row)?
+        .collect::<rusqlite::Result<_>>()?;
+    for shard in shards {
+        if wanted == 0 || items.len() >= wanted && items[wanted - 1].last_used >= shard.end {
+            break;
+        }
+        items.extend(with_shard(conn, &shard, || {
+            conn.prepare(SHARD_PAGE_SQL)?
+                .query_map(params![wanted], ClipItem::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?);
+        items.sort_by(by_last_used);
+        items.truncate(wanted);
+    }
+    Ok(items.into_iter().skip(offset as usize).collect())
+}
+
+// created_at 晚于 since 的记录，按复制时间先后排列
+// 分片中的记录最近使用时间都早于分片月份结束，不可能晚于 since 的分片直接跳过
+pub fn items_since(conn: &Connection, since: i64) -> rusqlite::Result<Vec<ClipItem>> {
+    let mut items: Vec<ClipItem> = conn
+        .prepare(select_items!(
+            "WHERE created_at > ?1 ORDER BY created_at, id"
+        ))?
+        .query_map(params![since], ClipItem::from_row)?
+        .collect::<rusqlite::Result<_>>()?;
+    for shard in list_shards(conn) {
+        if shard.end <= since {
+            break;
+        }
+        items.extend(with_shard(conn, &shard, || {
+            conn.prepare(select_items!("shard.", "WHERE created_at > ?1"))?
+                .query_map(params![since], ClipItem::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?);
+    }
+    items.sort_by_key(|item| (item.created_at, item.id));
+    Ok(items)
+}
+
+// 仍被记录引用的图片文件名，包括分片中的记录
+pub fn referenced_images(conn: &Connection) -> rusqlite::Result<HashSet<String>> {
+    // SVG 记录是文本，预览图的文件名在元数据中
+    let query = |schema: &str| -> rusqlite::Result<Vec<String>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT content FROM {schema}.items WHERE content_type = ?1
+             UNION SELECT json_extract(metadata, '$.svg.preview') FROM {schema}.items
+             WHERE json_extract(metadata, '$.svg.preview') IS NOT NULL",
+            schema = schema
+        ))?;
+        let rows = stmt.query_map(params![CONTENT_IMAGE], |row| row.get(0))?;
+        rows.collect()
+    };
+    let mut images: HashSet<String> = query("main")?.into_iter().collect();
+    images.extend(query_shards(conn, |_| query("shard"))?);
+    Ok(images)
+}
+
+// 归档的记录先移回主数据库再修改
+fn set_pinned(conn: &Connection, id: i64, pinned: bool) -> Result<(), String> {
+    let set = || {
+        conn.execute(
+            "UPDATE items SET pinned = ?1 WHERE id = ?2",
+            params![pinned, id],
+        )
+    };
+    let mut updated = set().map_err(|e| e.to_string())?;
+    if updated == 0 && restore_item(conn, id).map_err(|e| e.to_string())? {
+        updated = set().map_err(|e| e.to_string())?;
+    }
+    if updated == 0 {
+        return Err("记录不存在".into());
+    }
+    Ok(())
+}
+
+fn explain(conn: &Connection, sql: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+    let rows = stmt.query_map([], |row| {
+        let id: i64 = row.get("id")?;
+        let parent: i64 = row.get("parent")?;
+        let detail: String = row.get("detail")?;
+        Ok((id, parent, detail))
+    })?;
+
+    // 根据 parent 计算缩进层级，输出与 sqlite3 命令行类似的树形结构
+    let mut depths: Vec<(i64, usize)> = Vec::new();
+    let mut lines = Vec::new();
+    for row in rows {
+        let (id, parent, detail) = row?;
+        let depth = depths
+            .iter()
+            .find(|(node, _)| *node == parent)
+            .map(|(_, depth)| depth + 1)
+            .unwrap_or(0);
+        depths.push((id, depth));
+        lines.push(format!("{}{}", "  ".repeat(depth), detail));
+    }
+    Ok(lines)
+}
+
+// 调试用：输出所有热点查询的执行计划，用于排查慢查询
+#[tauri::command]
+pub fn get_query_plans(db: State<'_, Db>) -> Result<Vec<QueryPlan>, String> {
+    let conn = db.0.lock().unwrap();
+    HOT_QUERIES
+        .iter()
+        .map(|(name, sql)| {
+            Ok(QueryPlan {
+                name: name.to_string(),
+                sql: sql.to_string(),
+                plan: explain(&conn, sql).map_err(|e| e.to_string())?,
+            })
+        })
+        .collect()
+}
+
+fn generate_key() -> Result<String, String> {
+    let mut key = [0u8; DB_KEY_LEN];
+    getrandom::fill(&mut key).map_err(|e| e.to_string())?;
+    Ok(hex::encode(key))
+}
+
+// 用 sqlcipher_export 把当前数据库复制到 target，key 为 None 时导出为未加密的数据库
+fn export(conn: &Connection, target: &Path, key: Option<&str>) -> rusqlite::Result<()> {
+    conn.execute(
+        "ATTACH DATABASE ?1 AS export KEY ?2",
+        params![
+            target.to_string_lossy(),
+            key.map(raw_key).unwrap_or_default()
+        ],
+    )?;
+    let exported = conn
+        .query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()))
+        .and_then(|_| {
+            // sqlcipher_export 不会复制 user_version，需要单独设置，否则会重新执行迁移
+            let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+            conn.pragma_update(
+                Some(DatabaseName::Attached("export")),
+                "user_version",
+                version,
+            )
+        });
+    conn.execute("DETACH DATABASE export", [])?;
+    exported
+}
+
+// 关闭当前连接，用导出的文件替换数据库后重新打开
+fn replace_db(
+    conn: &mut Connection,
+    path: &Path,
+    exported: &Path,
+    key: Option<&str>,
+) -> Result<(), String> {
+    let memory = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    let old = std::mem::replace(conn, memory);
+    if let Err((old, e)) = old.close() {
+        *conn = old;
+        return Err(e.to_string());
+    }
+
+    let renamed = replace_file(path, exported);
+    // 替换失败时重新打开原来的数据库
+    let key = if renamed.is_ok() { key } else { None };
+    *conn = connect(path, key).map_err(|e| e.to_string())?;
+    renamed
+}
+
+fn replace_file(path: &Path, exported: &Path) -> Result<(), String> {
+    // 连接正常关闭后 WAL 文件已合并删除，这里清理异常情况下残留的文件，避免被合并到新数据库
+    for suffix in ["-wal", "-shm"] {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(name));
+    }
+    fs::rename(exported, path).map_err(|e| e.to_string())
+}
+
+// 把每个分片导出为使用新密钥的副本，返回分片和副本的路径；失败时删除已导出的副本
+fn export_shards(
+    conn: &Connection,
+    old_key: Option<&str>,
+    new_key: Option<&str>,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let mut exported = Vec::new();
+    for shard in list_shards(conn) {
+        let target = shard.path.with_extension("db.tmp");
+        let _ = fs::remove_file(&target);
+        let result = connect(&shard.path, old_key)
+            .and_then(|shard_conn| export(&shard_conn, &target, new_key));
+        if let Err(e) = result {
+            let _ = fs::remove_file(&target);
+            for (_, target) in &exported {
+                let _ = fs::remove_file(target);
+            }
+            return Err(e.to_string());
+        }
+        exported.push((shard.path, target));
+    }
+    Ok(exported)
+}
+
+#[tauri::command]
+pub fn is_history_encrypted() -> bool {
+    stored_key().is_some()
+}
+
+// 开启或关闭历史数据库加密：把数据库导出为加密（或未加密）的副本后替换原文件
+// 密钥随机生成并保存在系统钥匙串中，开启时已有的历史会一并加密
+#[tauri::command]
+pub fn set_history_encryption<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    enabled: bool,
+) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+    let old_key = get_secret(Secret::DatabaseKey)?;
+    if old_key.is_some() == enabled {
+        return Ok(());
+    }
+
+    let path = db_path(&app)?;
+    let exported = path.with_extension("db.tmp");
+    let _ = fs::remove_file(&exported);
+    let new_key = if enabled { Some(generate_key()?) } else { None };
+
+    let mut conn = db.0.lock().unwrap();
+    export(&conn, &exported, new_key.as_deref()).map_err(|e| {
+        let _ = fs::remove_file(&exported);
+        e.to_string()
+    })?;
+    let shards =
+        export_shards(&conn, old_key.as_deref(), new_key.as_deref()).inspect_err(|_| {
+            let _ = fs::remove_file(&exported);
+        })?;
+
+    // 先更新钥匙串再替换文件，替换失败时恢复原来的密钥
+    match &new_key {
+        Some(key) => set_secret(Secret::DatabaseKey, key)?,
+        None => delete_secret(Secret::DatabaseKey)?,
+    }
+    if let Err(e) = replace_db(&mut conn, &path, &exported, new_key.as_deref()) {
+        let _ = fs::remove_file(&exported);
+        for (_, target) in &shards {
+            let _ = fs::remove_file(target);
+        }
+        let restored = match &old_key {
+            Some(key) => set_secret(Secret::DatabaseKey, key),
+            None => delete_secret(Secret::DatabaseKey),
+        };
+        if let Err(e) = restored {
+            println!("Database - Failed to restore key: {}", e);
+        }
+        return Err(e);
+    }
+    // 主数据库已经替换，分片替换失败时只能记录下来，这个分片之后无法附加
+    for (shard, target) in shards {
+        if let Err(e) = replace_file(&shard, &target) {
+            println!("Database - Failed to replace shard {:?}: {}", shard, e);
+        }
+    }
+
+    println!(
+        "Database - Encryption {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+// 历史统计，供设置页展示
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStats {
+    pub total: i64,
+    pub pinned: i64,
+    // 遮盖保存的敏感记录
+    pub sensitive: i64,
+    // 含有密钥的记录
+    pub secrets: i64,
+}
+
+fn history_stats(conn: &Connection, schema: &str) -> rusqlite::Result<HistoryStats> {
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(*) AS total,
+                COALESCE(SUM(pinned), 0) AS pinned,
+                COUNT(json_extract(metadata, '$.sensitive')) AS sensitive,
+                COUNT(json_extract(metadata, '$.secret')) AS secrets
+             FROM {}.items",
+            schema
+        ),
+        [],
+        |row| {
+            Ok(HistoryStats {
+                total: row.get("total")?,
+                pinned: row.get("pinned")?,
+                sensitive: row.get("sensitive")?,
+                secrets: row.get("secrets")?,
+            })
+        },
+    )
+}
+
+#[tauri::command]
+pub fn get_history_stats(db: State<'_, Db>) -> Result<HistoryStats, String> {
+    let conn = db.0.lock().unwrap();
+    let mut stats = history_stats(&conn, "main").map_err(|e| e.to_string())?;
+    let archived = query_shards(&conn, |_| Ok(vec![history_stats(&conn, "shard")?]))
+        .map_err(|e| e.to_string())?;
+    for shard in archived {
+        stats.total += shard.total;
+        stats.pinned += shard.pinned;
+        stats.sensitive += shard.sensitive;
+        stats.secrets += shard.secrets;
+    }
+    Ok(stats)
+}
+
+// 列表返回的含有密钥的记录只有遮盖预览，见 sensitive::conceal
+fn conceal_items<R: Runtime>(app: &AppHandle<R>, mut items: Vec<ClipItem>) -> Vec<ClipItem> {
+    let settings = current_settings(app).capture.sensitive;
+    for item in &mut items {
+        conceal(item, &settings);
+    }
+    items
+}
+
+#[tauri::command]
+pub fn get_history<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<ClipItem>, String> {
+    lock.ensure_unlocked()?;
+    let items = history_page(&db.0.lock().unwrap(), limit, offset).map_err(|e| e.to_string())?;
+    Ok(conceal_items(&app, items))
+}
+
+#[tauri::command]
+pub fn get_pinned<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+) -> Result<Vec<ClipItem>, String> {
+    lock.ensure_unlocked()?;
+    let items = {
+        let conn = db.0.lock().unwrap();
+        let mut stmt = conn.prepare_cached(PINNED_SQL).map_err(|e| e.to_string())?;
+        stmt.query_map([], ClipItem::from_row)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| e.to_string())?
+    };
+    Ok(conceal_items(&app, items))
+}
+
+#[tauri::command]
+pub fn pin_item(db: State<'_, Db>, lock: State<'_, AppLock>, id: i64) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+    set_pinned(&db.0.lock().unwrap(), id, true)
+}
+
+#[tauri::command]
+pub fn unpin_item<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    id: i64,
+) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+    set_pinned(&db.0.lock().unwrap(), id, false)?;
+    unbind_target(&app, HotkeyTarget::Item(id));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_item<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    id: i64,
+) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+    {
+        let conn = db.0.lock().unwrap();
+        let Some(item) = get_item(&conn, id).map_err(|e| e.to_string())? else {
+            return Ok(());
+        };
+        conn.execute("DELETE FROM items WHERE id = ?1", params![id])
+            .map_err(|e| e.to_string())?;
+
+        // 没有其他记录（包括分片中的记录）引用时才删除图片文件
+        if item.content_type == CONTENT_IMAGE {
+            let referenced = referenced_images(&conn).map_err(|e| e.to_string())?;
+            if !referenced.contains(&item.content) {
+                remove_image_file(&app, &item.content);
+            }
+        }
+    }
+    unbind_target(&app, HotkeyTarget::Item(id));
+    refresh_tray_menu(&app);
+    Ok(())
+}
+
+// 清空历史，固定的记录及其图片会保留；固定的记录不会归档，分片整个删除
+#[tauri::command]
+pub fn clear_history<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+    {
+        let conn = db.0.lock().unwrap();
+        conn.execute("DELETE FROM items WHERE pinned = 0", [])
+            .map_err(|e| e.to_string())?;
+        for shard in list_shards(&conn) {
+            remove_shard(&shard);
+        }
+        let referenced = referenced_images(&conn).map_err(|e| e.to_string())?;
+        remove_unreferenced_images(&app, &referenced);
+    }
+    clear_previews(&app);
+    refresh_tray_menu(&app);
+    Ok(())
+}
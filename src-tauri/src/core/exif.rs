@@ -0,0 +1,206 @@
+// core/exif.rs
+// 图片元数据：照片中的 EXIF 可能包含拍摄位置、设备等隐私信息
+// 从剪贴板捕获的图片只保存像素，重新编码为 PNG，本身不带元数据；
+// 文件列表记录粘贴的是原始文件，开启去除元数据后粘贴去除了 EXIF/XMP 等信息的副本，无法去除时不粘贴
+use image::{
+    codecs::jpeg::JpegEncoder, metadata::Orientation, DynamicImage, ImageDecoder, ImageReader,
+};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::core::db::now_millis;
+
+const STRIPPED_DIR: &str = "stripped";
+// 副本的保留时间，剪贴板可能仍引用之前粘贴的副本，只清理超过这个时间的
+const STRIPPED_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const JPEG_QUALITY: u8 = 92;
+// JPEG 中需要去除的段：APP1（EXIF、XMP）、APP13（Photoshop/IPTC）、COM（注释）
+// 保留 APP0（JFIF）、APP2（ICC 颜色配置）和 APP14（Adobe），去除后颜色会不正确
+const JPEG_STRIPPED_MARKERS: &[u8] = &[0xE1, 0xED, 0xFE];
+// PNG 中需要去除的块
+const PNG_STRIPPED_CHUNKS: &[&[u8]] = &[b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+// 读取图片并按 EXIF 中的方向旋转，去除元数据后照片仍然以正确的方向显示
+pub fn open_oriented(path: &Path) -> Result<DynamicImage, String> {
+    let mut decoder = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| e.to_string())?
+        .into_decoder()
+        .map_err(|e| e.to_string())?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+fn orientation(path: &Path) -> Orientation {
+    ImageReader::open(path)
+        .ok()
+        .and_then(|reader| reader.with_guessed_format().ok())
+        .and_then(|reader| reader.into_decoder().ok())
+        .and_then(|mut decoder| decoder.orientation().ok())
+        .unwrap_or(Orientation::NoTransforms)
+}
+
+// 逐段复制 JPEG，跳过元数据段，图像数据不重新编码
+fn strip_jpeg(data: &[u8]) -> Option<Vec<u8>> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..2]);
+    let mut i = 2;
+    while i + 1 < data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        match marker {
+            // 段之间的填充字节
+            0xFF => i += 1,
+            // 没有长度的独立标记
+            0x01 | 0xD0..=0xD7 => {
+                output.extend_from_slice(&data[i..i + 2]);
+                i += 2;
+            }
+            // 扫描数据开始后不再有元数据，原样复制剩余部分
+            0xDA => {
+                output.extend_from_slice(&data[i..]);
+                return Some(output);
+            }
+            _ => {
+                let length = u16::from_be_bytes([*data.get(i + 2)?, *data.get(i + 3)?]) as usize;
+                let end = i + 2 + length;
+                let segment = data.get(i..end)?;
+                if !JPEG_STRIPPED_MARKERS.contains(&marker) {
+                    output.extend_from_slice(segment);
+                }
+                i = end;
+            }
+        }
+    }
+    None
+}
+
+// 逐块复制 PNG，跳过 EXIF 和文本块
+fn strip_png(data: &[u8]) -> Option<Vec<u8>> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(PNG_SIGNATURE);
+    let mut i = PNG_SIGNATURE.len();
+    while i < data.len() {
+        let length = u32::from_be_bytes(data.get(i..i + 4)?.try_into().ok()?) as usize;
+        // 长度、类型、数据、CRC
+        let end = i + 12 + length;
+        let chunk = data.get(i..end)?;
+        if !PNG_STRIPPED_CHUNKS.contains(&&chunk[4..8]) {
+            output.extend_from_slice(chunk);
+        }
+        i = end;
+    }
+    Some(output)
+}
+
+// 生成去除元数据的副本；方向不是默认值的 JPEG 需要按方向旋转后重新编码
+fn strip_file(source: &Path, target: &Path) -> Result<(), String> {
+    let extension = source
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let data = fs::read(source).map_err(|e| e.to_string())?;
+    let stripped = match extension.as_str() {
+        "jpg" | "jpeg" if orientation(source) != Orientation::NoTransforms => {
+            let image = open_oriented(source)?;
+            let file = File::create(target).map_err(|e| e.to_string())?;
+            return image
+                .to_rgb8()
+                .write_with_encoder(JpegEncoder::new_with_quality(
+                    BufWriter::new(file),
+                    JPEG_QUALITY,
+                ))
+                .map_err(|e| e.to_string());
+        }
+        "jpg" | "jpeg" => strip_jpeg(&data),
+        "png" => strip_png(&data),
+        _ => return Err("不支持的图片格式".into()),
+    };
+    let stripped = stripped.ok_or("无法解析图片文件")?;
+    fs::write(target, stripped).map_err(|e| e.to_string())
+}
+
+fn is_strippable(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| matches!(ext.as_str(), "jpg" | "jpeg" | "png"))
+}
+
+// 删除超过保留时间的副本目录
+fn remove_stale(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > STRIPPED_TTL);
+        if stale {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+// 把文件列表中的 JPEG、PNG 图片替换为去除元数据的副本，其他文件保持不变
+// 副本保存在 stripped 目录下每次粘贴单独的子目录中并保留原文件名
+// 任一图片无法去除元数据时返回错误，不回退到带有元数据的原文件
+pub fn strip_files<R: Runtime>(
+    app: &AppHandle<R>,
+    files: Vec<String>,
+) -> Result<Vec<String>, String> {
+    if !files.iter().any(|file| is_strippable(Path::new(file))) {
+        return Ok(files);
+    }
+    let root = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(STRIPPED_DIR);
+    remove_stale(&root);
+    let dir = root.join(now_millis().to_string());
+
+    let stripped: Result<Vec<String>, String> = files
+        .into_iter()
+        .enumerate()
+        .map(|(index, file)| {
+            let source = PathBuf::from(&file);
+            let Some(name) = source.file_name().filter(|_| is_strippable(&source)) else {
+                return Ok(file);
+            };
+            // 每个文件放在单独的子目录中，避免同名文件互相覆盖
+            let target_dir = dir.join(index.to_string());
+            let target = target_dir.join(name);
+            fs::create_dir_all(&target_dir)
+                .map_err(|e| e.to_string())
+                .and_then(|_| strip_file(&source, &target))
+                .map_err(|e| {
+                    println!("Exif - Failed to strip {}: {}", file, e);
+                    format!("去除 {} 的元数据失败: {}", name.to_string_lossy(), e)
+                })?;
+            Ok(target.to_string_lossy().into_owned())
+        })
+        .collect();
+    if stripped.is_err() {
+        let _ = fs::remove_dir_all(&dir);
+    }
+    stripped
+}
@@ -0,0 +1,125 @@
+// core/files.rs
+// 文件列表剪贴板：在资源管理器/访达/文件管理器中复制的文件，以路径列表的形式保存
+// Windows 为 CF_HDROP，macOS 为 NSFilenamesPboardType，Linux 为 file:// URI 列表
+use clipboard_rs::{Clipboard, ClipboardContext, ContentFormat};
+#[cfg(not(any(windows, target_os = "macos")))]
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use tauri::{AppHandle, Runtime};
+
+use crate::core::db::CONTENT_FILES;
+use crate::core::exif::strip_files;
+use crate::core::sensitive::get_revealed_item;
+use crate::core::settings::current_settings;
+
+// 读取剪贴板中的文件列表，统一转换为本地路径
+pub fn read_files(ctx: &ClipboardContext) -> Option<Vec<String>> {
+    if !ctx.has(ContentFormat::Files) {
+        return None;
+    }
+    let files: Vec<String> = ctx
+        .get_files()
+        .ok()?
+        .iter()
+        .map(|file| to_local_path(file))
+        .collect();
+    (!files.is_empty()).then_some(files)
+}
+
+// Linux 上得到的是 file:// URI，需要去掉前缀并解码 %XX 转义
+fn to_local_path(file: &str) -> String {
+    match file.strip_prefix("file://") {
+        Some(path) => percent_decode(path),
+        None => file.to_string(),
+    }
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| input.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// URI 路径中原样保留的字符，其余（空格、%、# 和非 ASCII 字符等）都转义
+#[cfg(not(any(windows, target_os = "macos")))]
+const PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+// 本地路径转换为 file:// URI，是 to_local_path 的逆操作
+#[cfg(not(any(windows, target_os = "macos")))]
+fn to_file_uri(path: &str) -> String {
+    format!("file://{}", utf8_percent_encode(path, PATH_ENCODE_SET))
+}
+
+// 将文件列表记录的 content（路径的 JSON 数组）以系统原生格式写入剪贴板
+// strip_metadata 为 None 时按设置决定是否写入去除了元数据的图片副本
+pub fn write_files<R: Runtime>(
+    app: &AppHandle<R>,
+    ctx: &ClipboardContext,
+    content: &str,
+    strip_metadata: Option<bool>,
+) -> Result<(), String> {
+    let mut files: Vec<String> = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    if strip_metadata.unwrap_or_else(|| current_settings(app).capture.strip_image_metadata) {
+        files = strip_files(app, files)?;
+    }
+    // Linux 上 clipboard-rs 只给路径加上 file:// 前缀，需要先转义成合法的 URI
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let files = files.iter().map(|file| to_file_uri(file)).collect();
+    ctx.set_files(files).map_err(|e| e.to_string())
+}
+
+// 将历史中的文件列表以系统原生格式写回剪贴板，可直接粘贴到文件管理器
+#[tauri::command]
+pub fn copy_files<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    strip_metadata: Option<bool>,
+    passcode: Option<String>,
+) -> Result<(), String> {
+    let item = get_revealed_item(&app, id, passcode.as_deref())?;
+    if item.content_type != CONTENT_FILES {
+        return Err("该记录不是文件列表".into());
+    }
+
+    let ctx = ClipboardContext::new().map_err(|e| e.to_string())?;
+    write_files(&app, &ctx, &item.content, strip_metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(any(windows, target_os = "macos")))]
+    fn file_uri_round_trip() {
+        let path = "/home/user/My Files/100% 报告#1.pdf";
+        let uri = to_file_uri(path);
+        assert_eq!(
+            uri,
+            "file:///home/user/My%20Files/100%25%20%E6%8A%A5%E5%91%8A%231.pdf"
+        );
+        assert_eq!(to_local_path(&uri), path);
+        assert_eq!(to_local_path(path), path);
+    }
+}
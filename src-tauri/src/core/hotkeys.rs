@@ -0,0 +1,318 @@
+// core/hotkeys.rs
+// 全局快捷键：显示/隐藏主窗口，以及可选的纯文本粘贴，快捷键保存在应用设置中，可在运行时修改
+// 可选注册 PrintScreen 键调用系统截图工具，截图保存到历史（见 screenshot.rs）
+// 还可以给片段或固定的记录绑定快捷键，不打开窗口直接粘贴；片段删除或记录取消固定、删除时解除绑定
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::core::db::{get_item, Db};
+use crate::core::paster::{paste_clipboard_plain, paste_entry, paste_next_entry};
+use crate::core::screenshot::{take_screenshot, PrintScreenMode, PRINT_SCREEN_SHORTCUT};
+use crate::core::settings::{current_settings, update};
+use crate::core::snippets::{get_snippet, paste_snippet_entry};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Hotkeys {
+    // 快捷键字符串，格式如 CommandOrControl+Shift+V
+    pub toggle_window: String,
+    // 去掉当前剪贴板内容的格式后粘贴，None 表示不注册
+    pub paste_plain: Option<String>,
+    // 粘贴连续粘贴队列中的下一条，None 表示不注册
+    pub paste_next: Option<String>,
+    // PrintScreen 键的处理方式，默认交给系统
+    pub print_screen: PrintScreenMode,
+    // 片段和固定记录的快捷键
+    pub items: Vec<ItemHotkey>,
+}
+
+// 快捷键粘贴的对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type", content = "id")]
+pub enum HotkeyTarget {
+    Snippet(i64),
+    Item(i64),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemHotkey {
+    pub shortcut: String,
+    pub target: HotkeyTarget,
+}
+
+impl Default for Hotkeys {
+    fn default() -> Self {
+        Self {
+            toggle_window: "CommandOrControl+Shift+V".into(),
+            paste_plain: None,
+            paste_next: None,
+            print_screen: PrintScreenMode::Off,
+            items: Vec::new(),
+        }
+    }
+}
+
+// 主窗口在前台时隐藏，否则显示并获取焦点
+fn toggle_main_window<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = app.get_webview_window("main") else {
+        println!("Hotkey - Main window not found");
+        return;
+    };
+
+    let visible = window.is_visible().unwrap_or(false);
+    let focused = window.is_focused().unwrap_or(false);
+    let minimized = window.is_minimized().unwrap_or(false);
+    if visible && focused && !minimized {
+        if let Err(e) = window.hide() {
+            println!("Hotkey - Failed to hide window: {:?}", e);
+        }
+    } else {
+        let _ = window.show();
+        let _ = window.unminimize();
+        if let Err(e) = window.set_focus() {
+            println!("Hotkey - Failed to set focus: {:?}", e);
+        }
+    }
+}
+
+// 按下快捷键时执行的动作
+#[derive(Debug, Clone, Copy)]
+enum HotkeyAction {
+    ToggleWindow,
+    PastePlain,
+    PasteNext,
+    Screenshot(PrintScreenMode),
+    Paste(HotkeyTarget),
+}
+
+// 已设置的快捷键及其动作，未设置的可选快捷键不包含在内
+fn bindings(hotkeys: &Hotkeys) -> Vec<(HotkeyAction, &str)> {
+    let mut bindings = vec![(HotkeyAction::ToggleWindow, hotkeys.toggle_window.as_str())];
+    if let Some(shortcut) = hotkeys.paste_plain.as_deref() {
+        bindings.push((HotkeyAction::PastePlain, shortcut));
+    }
+    if let Some(shortcut) = hotkeys.paste_next.as_deref() {
+        bindings.push((HotkeyAction::PasteNext, shortcut));
+    }
+    if hotkeys.print_screen.intercepts() {
+        bindings.push((
+            HotkeyAction::Screenshot(hotkeys.print_screen),
+            PRINT_SCREEN_SHORTCUT,
+        ));
+    }
+    for binding in &hotkeys.items {
+        bindings.push((
+            HotkeyAction::Paste(binding.target),
+            binding.shortcut.as_str(),
+        ));
+    }
+    bindings
+}
+
+fn register<R: Runtime>(
+    app: &AppHandle<R>,
+    shortcut: Shortcut,
+    action: HotkeyAction,
+) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _, event| {
+            match (action, event.state) {
+                (HotkeyAction::ToggleWindow, ShortcutState::Pressed) => toggle_main_window(app),
+                // 松开后再粘贴，避免快捷键的按键和模拟的 Ctrl+V 混在一起
+                (HotkeyAction::PastePlain, ShortcutState::Released) => paste_clipboard_plain(),
+                (HotkeyAction::PasteNext, ShortcutState::Released) => paste_next(app),
+                (HotkeyAction::Screenshot(mode), ShortcutState::Pressed) => take_screenshot(mode),
+                (HotkeyAction::Paste(target), ShortcutState::Released) => paste_target(app, target),
+                _ => {}
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+fn paste_target<R: Runtime>(app: &AppHandle<R>, target: HotkeyTarget) {
+    let pasted = match target {
+        HotkeyTarget::Snippet(id) => paste_snippet_entry(app, id),
+        HotkeyTarget::Item(id) => paste_entry(app, id, None, None),
+    };
+    if let Err(e) = pasted {
+        println!("Hotkey - Failed to paste {:?}: {}", target, e);
+    }
+}
+
+fn paste_next<R: Runtime>(app: &AppHandle<R>) {
+    match paste_next_entry(app) {
+        Ok(true) => {}
+        Ok(false) => println!("Hotkey - Paste stack is empty"),
+        Err(e) => println!("Hotkey - Failed to paste next item: {}", e),
+    }
+}
+
+fn unregister<R: Runtime>(app: &AppHandle<R>, shortcut: Shortcut) {
+    if app.global_shortcut().is_registered(shortcut) {
+        if let Err(e) = app.global_shortcut().unregister(shortcut) {
+            println!("Hotkey - Failed to unregister {}: {:?}", shortcut, e);
+        }
+    }
+}
+
+fn parse_shortcut(shortcut: &str) -> Result<Shortcut, String> {
+    shortcut
+        .parse()
+        .map_err(|e| format!("快捷键格式无效: {}", e))
+}
+
+// 解析所有快捷键，同一个快捷键不能用于两个动作
+fn parse_bindings(hotkeys: &Hotkeys) -> Result<Vec<(HotkeyAction, Shortcut)>, String> {
+    let mut parsed: Vec<(HotkeyAction, Shortcut)> = Vec::new();
+    for (action, shortcut) in bindings(hotkeys) {
+        let shortcut = parse_shortcut(shortcut)?;
+        if parsed
+            .iter()
+            .any(|(_, existing)| existing.id() == shortcut.id())
+        {
+            return Err(format!("快捷键重复: {}", shortcut));
+        }
+        parsed.push((action, shortcut));
+    }
+    Ok(parsed)
+}
+
+// 启动时注册快捷键，注册失败（例如已被其他应用占用）时只记录日志
+pub fn setup_hotkeys<R: Runtime>(app: &AppHandle<R>) {
+    let hotkeys = current_settings(app).hotkeys;
+    for (action, shortcut) in bindings(&hotkeys) {
+        let registered = parse_shortcut(shortcut).and_then(|parsed| register(app, parsed, action));
+        if let Err(e) = registered {
+            println!("Hotkey - Failed to register {}: {}", shortcut, e);
+        }
+    }
+}
+
+// 把快捷键从 old 换成 new，新快捷键注册失败时恢复原来的快捷键
+pub fn rebind_hotkeys<R: Runtime>(
+    app: &AppHandle<R>,
+    old: &Hotkeys,
+    new: &Hotkeys,
+) -> Result<(), String> {
+    let new_bindings = parse_bindings(new)?;
+    let old_bindings: Vec<(HotkeyAction, Shortcut)> = bindings(old)
+        .into_iter()
+        .filter_map(|(action, shortcut)| Some((action, parse_shortcut(shortcut).ok()?)))
+        .collect();
+
+    for (_, shortcut) in &old_bindings {
+        unregister(app, *shortcut);
+    }
+    for (index, (action, shortcut)) in new_bindings.iter().enumerate() {
+        if let Err(e) = register(app, *shortcut, *action) {
+            for (_, registered) in &new_bindings[..index] {
+                unregister(app, *registered);
+            }
+            for (action, shortcut) in &old_bindings {
+                let _ = register(app, *shortcut, *action);
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_hotkeys<R: Runtime>(app: AppHandle<R>) -> Hotkeys {
+    current_settings(&app).hotkeys
+}
+
+// 修改显示/隐藏主窗口的快捷键
+#[tauri::command]
+pub fn set_hotkey<R: Runtime>(app: AppHandle<R>, shortcut: String) -> Result<(), String> {
+    update(&app, |settings| settings.hotkeys.toggle_window = shortcut).map(|_| ())
+}
+
+// 修改纯文本粘贴的快捷键，None 或空字符串表示取消
+#[tauri::command]
+pub fn set_paste_plain_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let shortcut = shortcut.filter(|shortcut| !shortcut.trim().is_empty());
+    update(&app, |settings| settings.hotkeys.paste_plain = shortcut).map(|_| ())
+}
+
+// 片段被删除、记录被删除或取消固定时解除它的快捷键
+pub fn unbind_target<R: Runtime>(app: &AppHandle<R>, target: HotkeyTarget) {
+    let bound = current_settings(app)
+        .hotkeys
+        .items
+        .iter()
+        .any(|binding| binding.target == target);
+    if !bound {
+        return;
+    }
+    if let Err(e) = update(app, |settings| {
+        settings
+            .hotkeys
+            .items
+            .retain(|binding| binding.target != target)
+    }) {
+        println!("Hotkey - Failed to unbind {:?}: {}", target, e);
+    }
+}
+
+// 设置 PrintScreen 键的处理方式
+#[tauri::command]
+pub fn set_print_screen_mode<R: Runtime>(
+    app: AppHandle<R>,
+    mode: PrintScreenMode,
+) -> Result<(), String> {
+    update(&app, |settings| settings.hotkeys.print_screen = mode).map(|_| ())
+}
+
+// 修改粘贴队列中下一条的快捷键，None 或空字符串表示取消
+#[tauri::command]
+pub fn set_paste_next_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let shortcut = shortcut.filter(|shortcut| !shortcut.trim().is_empty());
+    update(&app, |settings| settings.hotkeys.paste_next = shortcut).map(|_| ())
+}
+
+// 给片段或固定的记录绑定快捷键，替换原来的绑定；shortcut 为 None 或空字符串表示解除绑定
+#[tauri::command]
+pub fn set_item_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    target: HotkeyTarget,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let shortcut = shortcut.filter(|shortcut| !shortcut.trim().is_empty());
+    if shortcut.is_some() {
+        let conn = db.0.lock().unwrap();
+        match target {
+            HotkeyTarget::Snippet(id) => {
+                if get_snippet(&conn, id).map_err(|e| e.to_string())?.is_none() {
+                    return Err("片段不存在".into());
+                }
+            }
+            HotkeyTarget::Item(id) => {
+                let item = get_item(&conn, id)
+                    .map_err(|e| e.to_string())?
+                    .ok_or("记录不存在")?;
+                if !item.pinned {
+                    return Err("只能给固定的记录设置快捷键".into());
+                }
+            }
+        }
+    }
+
+    update(&app, |settings| {
+        let items = &mut settings.hotkeys.items;
+        items.retain(|binding| binding.target != target);
+        if let Some(shortcut) = shortcut {
+            items.push(ItemHotkey { shortcut, target });
+        }
+    })
+    .map(|_| ())
+}
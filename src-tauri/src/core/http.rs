@@ -0,0 +1,253 @@
+// core/http.rs
+// 共享 HTTP 客户端：链接信息、同步、翻译等联网功能都通过 http_client 创建客户端，统一遵循代理设置
+// System 模式读取 HTTP(S)_PROXY 环境变量和系统代理设置（Windows 注册表、macOS 网络设置），
+// Pac 模式下载设置中的 PAC 脚本并编译一次，按请求的来源执行脚本选择代理，结果缓存在编译后的脚本中
+use reqwest::{Client, NoProxy, Proxy, Url};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Runtime, State};
+use tokio::{
+    runtime::{Handle, RuntimeFlavor},
+    task::block_in_place,
+};
+
+use crate::core::keystore::{delete_secret, get_secret, has_secret, set_secret, Secret};
+use crate::core::lock::AppLock;
+use crate::core::pac::{first_proxy, PacScript};
+use crate::core::settings::{current_settings, update};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const USER_AGENT: &str = concat!("Clipper/", env!("CARGO_PKG_VERSION"));
+// 检查网络连通性时访问的地址，与更新检查使用同一个站点
+const CONNECTIVITY_URL: &str = "https://github.com";
+// PAC 脚本的缓存时间，过期后在下次创建客户端时重新下载
+const PAC_TTL: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    #[default]
+    System,
+    None,
+    Manual,
+    Pac,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProxySettings {
+    pub mode: ProxyMode,
+    // 手动代理地址，例如 http://proxy.corp.local:8080
+    pub url: String,
+    // 不走代理的主机，逗号分隔，例如 localhost,.corp.local
+    pub bypass: String,
+    // 需要认证的代理的用户名，密码保存在系统钥匙串中
+    pub username: String,
+    // PAC 脚本地址，支持 http(s):// 和 file://
+    pub pac_url: String,
+}
+
+struct CachedPac {
+    url: String,
+    script: Arc<PacScript>,
+    loaded: Instant,
+}
+
+static PAC_CACHE: Mutex<Option<CachedPac>> = Mutex::new(None);
+
+fn authenticate(proxy: Proxy, settings: &ProxySettings) -> Result<Proxy, String> {
+    let proxy = proxy.no_proxy(NoProxy::from_string(&settings.bypass));
+    if settings.username.is_empty() {
+        return Ok(proxy);
+    }
+    let password = get_secret(Secret::ProxyPassword)?.unwrap_or_default();
+    Ok(proxy.basic_auth(&settings.username, &password))
+}
+
+// 在 tokio 工作线程上执行可能阻塞的 f 时先把线程上的其他任务移走，不在运行时中时直接执行
+fn blocking<T>(f: impl FnOnce() -> T) -> T {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => block_in_place(f),
+        _ => f(),
+    }
+}
+
+// 按 PAC 脚本为请求选择代理，脚本执行出错时直连
+// 与浏览器一样只把协议、主机和端口交给脚本，同一来源的请求使用缓存的结果
+fn pac_proxy(script: &PacScript, url: &Url) -> Option<Url> {
+    let host = url.host_str()?;
+    let origin = format!("{}/", url.origin().ascii_serialization());
+    let result = match script.cached(&origin) {
+        Some(result) => result,
+        // 脚本可能解析 DNS，不能直接阻塞异步任务所在的线程
+        None => blocking(|| script.find_proxy_for_url(&origin, host)),
+    };
+    match result {
+        Ok(result) => first_proxy(&result)?.parse().ok(),
+        Err(e) => {
+            println!("Http - {}", e);
+            None
+        }
+    }
+}
+
+fn build_client(settings: &ProxySettings, pac: Option<Arc<PacScript>>) -> Result<Client, String> {
+    // reqwest 未内置 TLS 加密实现，与更新插件一样使用 ring，重复安装会被忽略
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(REQUEST_TIMEOUT);
+    let builder = match settings.mode {
+        ProxyMode::System => builder,
+        ProxyMode::None => builder.no_proxy(),
+        ProxyMode::Manual => {
+            let proxy =
+                Proxy::all(settings.url.trim()).map_err(|e| format!("代理地址无效: {}", e))?;
+            builder.proxy(authenticate(proxy, settings)?)
+        }
+        ProxyMode::Pac => {
+            let script = pac.ok_or("PAC 脚本尚未加载")?;
+            let proxy = Proxy::custom(move |url| pac_proxy(&script, url));
+            builder.proxy(authenticate(proxy, settings)?)
+        }
+    };
+    builder.build().map_err(|e| e.to_string())
+}
+
+fn pac_location(url: &str) -> Result<Url, String> {
+    let location = Url::parse(url.trim()).map_err(|e| format!("PAC 地址无效: {}", e))?;
+    match location.scheme() {
+        "http" | "https" | "file" => Ok(location),
+        _ => Err("PAC 地址只支持 http、https 和 file".into()),
+    }
+}
+
+// 读取并编译 PAC 脚本，按地址缓存；脚本本身直连下载
+async fn load_pac(url: &str) -> Result<Arc<PacScript>, String> {
+    if let Some(cached) = PAC_CACHE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|cached| cached.url == url && cached.loaded.elapsed() < PAC_TTL)
+    {
+        return Ok(cached.script.clone());
+    }
+
+    let location = pac_location(url)?;
+    let script = if location.scheme() == "file" {
+        let path = location.to_file_path().map_err(|_| "PAC 地址无效")?;
+        fs::read_to_string(path).map_err(|e| format!("读取 PAC 脚本失败: {}", e))?
+    } else {
+        let direct = ProxySettings {
+            mode: ProxyMode::None,
+            ..Default::default()
+        };
+        build_client(&direct, None)?
+            .get(location)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("下载 PAC 脚本失败: {}", e))?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    // 编译时会执行一次脚本，同样可能解析 DNS
+    let script = spawn_blocking(move || PacScript::compile(&script))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let script = Arc::new(script);
+    *PAC_CACHE.lock().unwrap() = Some(CachedPac {
+        url: url.to_string(),
+        script: script.clone(),
+        loaded: Instant::now(),
+    });
+    println!("Http - Loaded PAC script from {}", url);
+    Ok(script)
+}
+
+// 按当前代理设置创建 HTTP 客户端，所有联网请求都应通过它发出
+pub async fn http_client<R: Runtime>(app: &AppHandle<R>) -> Result<Client, String> {
+    let settings = current_settings(app).proxy;
+    let pac = match settings.mode {
+        ProxyMode::Pac => Some(load_pac(&settings.pac_url).await?),
+        _ => None,
+    };
+    build_client(&settings, pac)
+}
+
+// 保存代理设置前先用新设置创建一次客户端，代理地址无效时直接返回错误
+// PAC 脚本在第一次联网时才下载，这里只检查地址
+pub fn validate_proxy(settings: &ProxySettings) -> Result<(), String> {
+    if settings.mode == ProxyMode::Pac {
+        return pac_location(&settings.pac_url).map(|_| ());
+    }
+    build_client(settings, None).map(|_| ())
+}
+
+#[tauri::command]
+pub fn get_proxy_settings<R: Runtime>(app: AppHandle<R>) -> ProxySettings {
+    current_settings(&app).proxy
+}
+
+#[tauri::command]
+pub fn set_proxy_settings<R: Runtime>(
+    app: AppHandle<R>,
+    settings: ProxySettings,
+) -> Result<(), String> {
+    update(&app, |current| current.proxy = settings).map(|_| ())
+}
+
+// 保存代理密码到系统钥匙串，None 或空字符串表示删除
+// 密码只能写入，不能通过命令读出
+#[tauri::command]
+pub fn set_proxy_password(
+    lock: State<'_, AppLock>,
+    password: Option<String>,
+) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+    match password.filter(|password| !password.is_empty()) {
+        Some(password) => set_secret(Secret::ProxyPassword, &password),
+        None => delete_secret(Secret::ProxyPassword),
+    }
+}
+
+#[tauri::command]
+pub fn has_proxy_password() -> Result<bool, String> {
+    has_secret(Secret::ProxyPassword)
+}
+
+// 更新检查由前端的 updater 插件发起，手动代理和 PAC 时前端需要把这里返回的地址传给 check()
+// System 模式下 updater 插件本身会读取系统代理；PAC 按更新站点执行脚本得到代理
+// 代理密码不会返回给前端，因此更新检查不支持需要认证的代理
+#[tauri::command]
+pub async fn get_update_proxy<R: Runtime>(app: AppHandle<R>) -> Option<String> {
+    let settings = current_settings(&app).proxy;
+    match settings.mode {
+        ProxyMode::Manual => Some(settings.url.trim().to_string()),
+        ProxyMode::Pac => {
+            let script = load_pac(&settings.pac_url).await.ok()?;
+            let url = Url::parse(CONNECTIVITY_URL).ok()?;
+            pac_proxy(&script, &url).map(|proxy| proxy.to_string())
+        }
+        _ => None,
+    }
+}
+
+// 按当前代理设置访问外网，返回 HTTP 状态码，用于在设置页检查代理是否可用
+#[tauri::command]
+pub async fn test_connection<R: Runtime>(app: AppHandle<R>) -> Result<u16, String> {
+    let client = http_client(&app).await?;
+    let response = client
+        .get(CONNECTIVITY_URL)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(response.status().as_u16())
+}
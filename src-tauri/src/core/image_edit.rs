@@ -0,0 +1,100 @@
+// core/image_edit.rs
+// 图片编辑：对历史中的图片依次执行裁剪、旋转、缩放、灰度等操作，结果保存为一条新记录，原记录保持不变
+use clipboard_rs::{common::RustImage, RustImageData};
+use image::{imageops::FilterType, DynamicImage};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Runtime, State};
+
+use crate::core::clipboard_watcher::ingest_image;
+use crate::core::db::{get_item, ClipItem, Db, CONTENT_IMAGE};
+use crate::core::images::images_dir;
+use crate::core::lock::AppLock;
+
+// 缩放后的最大边长，避免生成过大的图片
+const MAX_DIMENSION: u32 = 16384;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ImageOp {
+    Crop {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    // 顺时针旋转，只支持 90 的倍数
+    Rotate {
+        degrees: u32,
+    },
+    Resize {
+        width: u32,
+        height: u32,
+    },
+    Grayscale,
+}
+
+fn apply(image: DynamicImage, op: &ImageOp) -> Result<DynamicImage, String> {
+    match *op {
+        ImageOp::Crop {
+            x,
+            y,
+            width,
+            height,
+        } => {
+            let in_bounds = x.checked_add(width).is_some_and(|r| r <= image.width())
+                && y.checked_add(height).is_some_and(|b| b <= image.height());
+            if width == 0 || height == 0 || !in_bounds {
+                return Err("裁剪区域超出图片范围".into());
+            }
+            Ok(image.crop_imm(x, y, width, height))
+        }
+        ImageOp::Rotate { degrees } => match degrees % 360 {
+            0 => Ok(image),
+            90 => Ok(image.rotate90()),
+            180 => Ok(image.rotate180()),
+            270 => Ok(image.rotate270()),
+            _ => Err("只能旋转 90 度的倍数".into()),
+        },
+        ImageOp::Resize { width, height } => {
+            if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+                return Err("图片尺寸无效".into());
+            }
+            Ok(image.resize_exact(width, height, FilterType::Lanczos3))
+        }
+        ImageOp::Grayscale => Ok(image.grayscale()),
+    }
+}
+
+// 按 ops 的顺序编辑图片记录，返回保存的新记录
+#[tauri::command]
+pub fn edit_image<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    id: i64,
+    ops: Vec<ImageOp>,
+) -> Result<ClipItem, String> {
+    lock.ensure_unlocked()?;
+    if ops.is_empty() {
+        return Err("没有需要执行的操作".into());
+    }
+    let item = get_item(&db.0.lock().unwrap(), id)
+        .map_err(|e| e.to_string())?
+        .ok_or("记录不存在")?;
+    if item.content_type != CONTENT_IMAGE {
+        return Err("该记录不是图片".into());
+    }
+
+    let path = images_dir(&app)?.join(&item.content);
+    let image = image::open(&path).map_err(|e| e.to_string())?;
+    let edited = ops.iter().try_fold(image, apply)?;
+
+    let metadata = json!({ "edited": { "from": id, "ops": ops } });
+    ingest_image(
+        &app,
+        &RustImageData::from_dynamic_image(edited),
+        Some(metadata),
+    )?
+    .ok_or_else(|| "编辑后的图片未被保存".into())
+}
@@ -0,0 +1,110 @@
+// core/images.rs
+// 图片剪贴板：图片编码为 PNG 后保存在应用数据目录的 images 子目录，历史记录中只保存文件名
+// 动图保存原始的 GIF/APNG 数据（见 animated.rs），另有同名的 .thumb.png 缩略图
+use clipboard_rs::{common::RustImage, Clipboard, ClipboardContext, RustImageData};
+use std::{collections::HashSet, fs, path::PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::core::animated::{animation_format, write_animated};
+use crate::core::db::{content_hash, ClipItem, CONTENT_IMAGE};
+use crate::core::sensitive::get_revealed_item;
+
+const IMAGES_DIR: &str = "images";
+const THUMBNAIL_SUFFIX: &str = ".thumb.png";
+
+pub fn images_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(IMAGES_DIR);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+// 图片文件对应的缩略图文件名
+pub fn thumbnail_name(file_name: &str) -> String {
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+    format!("{}{}", stem, THUMBNAIL_SUFFIX)
+}
+
+// 图片像素数据的哈希，用于判断剪贴板中的图片是否变化
+pub fn image_hash(image: &RustImageData) -> Option<String> {
+    image
+        .to_rgba8()
+        .ok()
+        .map(|rgba| content_hash(rgba.as_raw()))
+}
+
+// 以哈希作为文件名保存 PNG，相同的图片只保存一份，返回文件名
+pub fn save_image<R: Runtime>(
+    app: &AppHandle<R>,
+    image: &RustImageData,
+    hash: &str,
+) -> Result<String, String> {
+    let file_name = format!("{}.png", hash);
+    let path = images_dir(app)?.join(&file_name);
+    if !path.exists() {
+        let png = image.to_png().map_err(|e| e.to_string())?;
+        fs::write(&path, png.get_bytes()).map_err(|e| e.to_string())?;
+    }
+    Ok(file_name)
+}
+
+// 删除图片文件，动图的缩略图一起删除
+pub fn remove_image_file<R: Runtime>(app: &AppHandle<R>, file_name: &str) {
+    if let Ok(dir) = images_dir(app) {
+        if let Err(e) = fs::remove_file(dir.join(file_name)) {
+            println!("Failed to remove image {}: {:?}", file_name, e);
+        }
+        let thumbnail = dir.join(thumbnail_name(file_name));
+        if thumbnail.exists() {
+            let _ = fs::remove_file(thumbnail);
+        }
+    }
+}
+
+// 删除不再被任何记录引用的图片文件，referenced 为仍被引用的文件名
+pub fn remove_unreferenced_images<R: Runtime>(app: &AppHandle<R>, referenced: &HashSet<String>) {
+    let Ok(entries) = images_dir(app).and_then(|dir| fs::read_dir(dir).map_err(|e| e.to_string()))
+    else {
+        return;
+    };
+    let thumbnails: HashSet<String> = referenced.iter().map(|name| thumbnail_name(name)).collect();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if !referenced.contains(&file_name) && !thumbnails.contains(&file_name) {
+            remove_image_file(app, &file_name);
+        }
+    }
+}
+
+// 将 images 目录下保存的图片写入系统剪贴板，动图写回原始数据
+pub fn write_image<R: Runtime>(
+    app: &AppHandle<R>,
+    ctx: &ClipboardContext,
+    item: &ClipItem,
+) -> Result<(), String> {
+    if let Some(format) = animation_format(item.metadata.as_ref()) {
+        return write_animated(app, ctx, &item.content, format);
+    }
+    let path = images_dir(app)?.join(&item.content);
+    let image = RustImageData::from_path(&path.to_string_lossy()).map_err(|e| e.to_string())?;
+    ctx.set_image(image).map_err(|e| e.to_string())
+}
+
+// 将历史中的图片重新写入系统剪贴板
+#[tauri::command]
+pub fn copy_image<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    passcode: Option<String>,
+) -> Result<(), String> {
+    let item = get_revealed_item(&app, id, passcode.as_deref())?;
+    if item.content_type != CONTENT_IMAGE {
+        return Err("该记录不是图片".into());
+    }
+
+    let ctx = ClipboardContext::new().map_err(|e| e.to_string())?;
+    write_image(&app, &ctx, &item)
+}
@@ -0,0 +1,141 @@
+// core/issue.rs
+// 提 Issue 的粘贴助手：把复制的堆栈或日志整理成带环境信息的 Issue 正文，把 Issue 链接转换成带标题的 Markdown 链接
+// 结果写回剪贴板，可以直接粘贴到 GitHub/Jira
+use chrono::Local;
+use regex::Regex;
+use serde_json::Value;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Runtime};
+
+use crate::core::clipboard_watcher::write_text;
+use crate::core::db::CONTENT_TEXT;
+use crate::core::http::http_client;
+use crate::core::journal::code_fence;
+use crate::core::rules::expand;
+use crate::core::sensitive::get_revealed_item;
+use crate::core::settings::current_settings;
+
+// Issue 正文的默认模板，可以使用 {os}、{arch}、{version}、{date}、{time}、{content}、{code}（带代码块的内容）
+pub const DEFAULT_ISSUE_TEMPLATE: &str =
+    "### 环境\n\n- 系统：{os} {arch}\n- 版本：Clipper {version}\n- 时间：{date} {time}\n\n### 日志\n\n{code}\n";
+
+fn github_issue_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^https://github\.com/([^/]+)/([^/]+)/(?:issues|pull)/(\d+)").unwrap()
+    })
+}
+
+pub fn title_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap())
+}
+
+fn text_item<R: Runtime>(
+    app: &AppHandle<R>,
+    id: i64,
+    passcode: Option<&str>,
+) -> Result<String, String> {
+    let item = get_revealed_item(app, id, passcode)?;
+    if item.content_type != CONTENT_TEXT {
+        return Err("只能转换文本记录".into());
+    }
+    Ok(item.content)
+}
+
+pub fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+// GitHub 的 Issue 和 PR 通过 API 获取标题，链接文字为 “标题 (owner/repo#123)”
+async fn github_title(url: &str, client: &reqwest::Client) -> Option<String> {
+    let captures = github_issue_pattern().captures(url)?;
+    let (owner, repo, number) = (&captures[1], &captures[2], &captures[3]);
+    let api = format!(
+        "https://api.github.com/repos/{}/{}/issues/{}",
+        owner, repo, number
+    );
+    let body = client.get(api).send().await.ok()?.text().await.ok()?;
+    let issue: Value = serde_json::from_str(&body).ok()?;
+    let title = issue.get("title")?.as_str()?;
+    Some(format!("{} ({}/{}#{})", title, owner, repo, number))
+}
+
+// 其他网址（Jira 等）读取网页标题，去掉站点名称后缀
+async fn page_title(url: &str, client: &reqwest::Client) -> Option<String> {
+    let html = client.get(url).send().await.ok()?.text().await.ok()?;
+    let title = decode_entities(title_pattern().captures(&html)?[1].trim());
+    let title = [" - Jira", " - JIRA"]
+        .iter()
+        .find_map(|suffix| title.strip_suffix(suffix))
+        .unwrap_or(&title)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+// Markdown 链接文字需要转义方括号
+fn escape_link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+// 把复制的堆栈或日志按模板整理成 Issue 正文，写入剪贴板并返回
+#[tauri::command]
+pub fn copy_as_issue_body<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    passcode: Option<String>,
+) -> Result<String, String> {
+    let content = text_item(&app, id, passcode.as_deref())?;
+    let content = content.trim_end();
+    let fence = code_fence(content);
+    let code = format!("{}\n{}\n{}", fence, content, fence);
+    let version = app.package_info().version.to_string();
+
+    let body = expand(
+        &current_settings(&app).issue_template,
+        &Local::now(),
+        &[
+            ("os", std::env::consts::OS),
+            ("arch", std::env::consts::ARCH),
+            ("version", &version),
+            ("content", content),
+            ("code", &code),
+        ],
+    );
+    write_text(&body)?;
+    Ok(body)
+}
+
+// 把复制的 Issue 链接转换成带标题的 Markdown 链接，获取不到标题时以网址作为链接文字
+#[tauri::command]
+pub async fn copy_as_issue_link<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    passcode: Option<String>,
+) -> Result<String, String> {
+    let content = text_item(&app, id, passcode.as_deref())?;
+    let url = content.trim();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("该记录不是链接".into());
+    }
+
+    let client = http_client(&app).await?;
+    let title = match github_title(url, &client).await {
+        Some(title) => Some(title),
+        None => page_title(url, &client).await,
+    };
+    let link = format!(
+        "[{}]({})",
+        escape_link_text(title.as_deref().unwrap_or(url)),
+        url
+    );
+    write_text(&link)?;
+    Ok(link)
+}
@@ -0,0 +1,194 @@
+// core/journal.rs
+// 导出 Markdown 日志：按天分节写入历史，代码放在代码块中，图片复制到日志旁的 clipper-images 目录并以相对路径嵌入
+// 每次导出后在文件末尾写入导出位置标记，再次导出到同一文件时只追加之后的新记录，适合 Obsidian/Logseq 的每日笔记
+use chrono::{Local, NaiveDate, TimeZone};
+use serde_json::Value;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+use tauri::{AppHandle, Runtime, State};
+
+use crate::core::db::{items_since, ClipItem, Db, CONTENT_FILES, CONTENT_IMAGE};
+use crate::core::images::images_dir;
+use crate::core::lock::AppLock;
+use crate::core::sensitive::is_secret;
+use crate::core::settings::current_settings;
+
+const IMAGES_DIR: &str = "clipper-images";
+// 导出位置标记，记录已导出的最后一条记录的 created_at
+const MARKER_PREFIX: &str = "<!-- clipper:exported-until ";
+const MARKER_SUFFIX: &str = " -->";
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+// 已导出到的位置和最后一个日期标题
+fn read_progress(existing: &str) -> (i64, Option<String>) {
+    let since = existing
+        .lines()
+        .rev()
+        .find_map(|line| {
+            line.strip_prefix(MARKER_PREFIX)?
+                .strip_suffix(MARKER_SUFFIX)?
+                .parse()
+                .ok()
+        })
+        .unwrap_or(0);
+    let last_day = existing
+        .lines()
+        .rev()
+        .filter_map(|line| line.strip_prefix("## "))
+        .find(|day| NaiveDate::parse_from_str(day.trim(), DAY_FORMAT).is_ok())
+        .map(|day| day.trim().to_string());
+    (since, last_day)
+}
+
+// 多行且大部分行带有缩进或以代码常见的符号结尾时视为代码
+fn looks_like_code(text: &str) -> bool {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.len() < 2 {
+        return false;
+    }
+    let code_lines = lines
+        .iter()
+        .filter(|line| {
+            line.starts_with([' ', '\t'])
+                || line
+                    .trim_end()
+                    .ends_with([';', '{', '}', '(', ')', ',', ':'])
+        })
+        .count();
+    code_lines * 2 >= lines.len()
+}
+
+// 代码块的围栏需要比内容中最长的连续反引号更长
+pub fn code_fence(text: &str) -> String {
+    let longest = text.split(|ch| ch != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+// 把图片复制到日志旁的图片目录，返回相对路径
+fn copy_image<R: Runtime>(
+    app: &AppHandle<R>,
+    journal_dir: &Path,
+    file_name: &str,
+) -> Result<String, String> {
+    let target_dir = journal_dir.join(IMAGES_DIR);
+    fs::create_dir_all(&target_dir).map_err(|e| e.to_string())?;
+    let target = target_dir.join(file_name);
+    if !target.exists() {
+        fs::copy(images_dir(app)?.join(file_name), &target).map_err(|e| e.to_string())?;
+    }
+    Ok(format!("{}/{}", IMAGES_DIR, file_name))
+}
+
+fn render_item<R: Runtime>(app: &AppHandle<R>, journal_dir: &Path, item: &ClipItem) -> String {
+    let time = Local
+        .timestamp_millis_opt(item.created_at)
+        .single()
+        .map(|time| time.format("%H:%M").to_string())
+        .unwrap_or_default();
+    let source = item
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.pointer("/origin/app"))
+        .and_then(Value::as_str);
+    let mut entry = match source {
+        Some(source) => format!("**{}** · {}\n\n", time, source),
+        None => format!("**{}**\n\n", time),
+    };
+
+    match item.content_type.as_str() {
+        CONTENT_IMAGE => match copy_image(app, journal_dir, &item.content) {
+            Ok(path) => entry.push_str(&format!("![]({})\n", path)),
+            Err(e) => {
+                println!("Journal - Failed to copy image {}: {}", item.content, e);
+                entry.push_str("*（图片已丢失）*\n");
+            }
+        },
+        CONTENT_FILES => {
+            let paths: Vec<String> = serde_json::from_str(&item.content).unwrap_or_default();
+            for path in paths {
+                entry.push_str(&format!("- `{}`\n", path));
+            }
+        }
+        _ if looks_like_code(&item.content) => {
+            let fence = code_fence(&item.content);
+            entry.push_str(&format!(
+                "{}\n{}\n{}\n",
+                fence,
+                item.content.trim_end(),
+                fence
+            ));
+        }
+        // 普通文本作为引用块，避免内容中的 Markdown 标题打乱日志结构
+        _ => {
+            for line in item.content.trim_end().lines() {
+                entry.push_str(&format!("> {}\n", line));
+            }
+        }
+    }
+    entry.push('\n');
+    entry
+}
+
+// 把上次导出之后的新记录追加到 Markdown 文件，文件不存在时创建，返回导出的记录数
+#[tauri::command]
+pub fn export_journal<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    path: String,
+) -> Result<usize, String> {
+    lock.ensure_unlocked()?;
+    let path = Path::new(&path);
+    let journal_dir = path.parent().ok_or("导出路径无效")?;
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let (since, mut last_day) = read_progress(&existing);
+
+    let items = items_since(&db.0.lock().unwrap(), since).map_err(|e| e.to_string())?;
+    let Some(last) = items.last() else {
+        return Ok(0);
+    };
+
+    // 含有密钥的记录默认不导出，但导出位置仍然越过它们
+    let export_secrets = current_settings(&app).capture.sensitive.export_secrets;
+    let exported: Vec<&ClipItem> = items
+        .iter()
+        .filter(|item| export_secrets || !is_secret(item.metadata.as_ref()))
+        .collect();
+
+    let mut output = String::new();
+    if !existing.is_empty() && !existing.ends_with("\n\n") {
+        output.push('\n');
+    }
+    for item in &exported {
+        let day = Local
+            .timestamp_millis_opt(item.created_at)
+            .single()
+            .map(|time| time.format(DAY_FORMAT).to_string())
+            .unwrap_or_default();
+        if last_day.as_deref() != Some(day.as_str()) {
+            output.push_str(&format!("## {}\n\n", day));
+            last_day = Some(day);
+        }
+        output.push_str(&render_item(&app, journal_dir, item));
+    }
+    output.push_str(&format!(
+        "{}{}{}\n",
+        MARKER_PREFIX, last.created_at, MARKER_SUFFIX
+    ));
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    file.write_all(output.as_bytes())
+        .map_err(|e| e.to_string())?;
+    println!("Journal - Exported {} items", exported.len());
+    Ok(exported.len())
+}
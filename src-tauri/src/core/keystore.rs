@@ -0,0 +1,60 @@
+// core/keystore.rs
+// 系统钥匙串：Windows 凭据管理器、macOS 钥匙串、Linux Secret Service
+// 数据库密钥、代理密码等机密只保存在这里，不写入 settings.json
+// 这些函数只供后端模块使用，不要注册为命令：前端最多只能写入或查询是否存在，不能读出明文
+use keyring::{Entry, Error};
+
+// 与 tauri.conf.json 中的 identifier 一致，便于在钥匙串中识别
+const SERVICE: &str = "com.example.clipper";
+
+// 应用保存的机密，新增机密时在这里登记，避免各模块随意使用条目名称
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Secret {
+    // 历史数据库的 SQLCipher 密钥（十六进制）
+    DatabaseKey,
+    // 手动代理的密码
+    ProxyPassword,
+    // 应用锁密码的 argon2 哈希
+    LockPasscode,
+}
+
+impl Secret {
+    fn name(self) -> &'static str {
+        match self {
+            Secret::DatabaseKey => "history-db-key",
+            Secret::ProxyPassword => "proxy-password",
+            Secret::LockPasscode => "lock-passcode",
+        }
+    }
+}
+
+fn entry(secret: Secret) -> Result<Entry, String> {
+    Entry::new(SERVICE, secret.name()).map_err(|e| e.to_string())
+}
+
+// 读取机密，不存在时返回 None
+pub fn get_secret(secret: Secret) -> Result<Option<String>, String> {
+    match entry(secret)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn set_secret(secret: Secret, value: &str) -> Result<(), String> {
+    entry(secret)?
+        .set_password(value)
+        .map_err(|e| e.to_string())
+}
+
+// 删除机密，不存在时视为成功
+pub fn delete_secret(secret: Secret) -> Result<(), String> {
+    match entry(secret)?.delete_credential() {
+        Ok(()) | Err(Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn has_secret(secret: Secret) -> Result<bool, String> {
+    get_secret(secret).map(|value| value.is_some())
+}
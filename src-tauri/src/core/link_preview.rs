@@ -0,0 +1,218 @@
+// core/link_preview.rs
+// 链接信息：复制的文本是一个网址时，在后台获取网页标题和网站图标，保存到记录元数据的 link 字段
+// 列表中可以显示标题而不是原始网址；请求遵循代理设置并有超时，可以在设置中关闭
+use base64::{engine::general_purpose::STANDARD, Engine};
+use regex::Regex;
+use reqwest::{header::CONTENT_TYPE, Client, Response, Url};
+use serde_json::{json, Value};
+use std::{sync::OnceLock, time::Duration};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::core::db::{get_item, now_millis, update_metadata, ClipItem, Db, CONTENT_TEXT};
+use crate::core::http::http_client;
+use crate::core::issue::{decode_entities, title_pattern};
+use crate::core::lock::AppLock;
+use crate::core::sensitive::is_flagged;
+use crate::core::settings::current_settings;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(8);
+// 标题和图标地址都在 <head> 中，只读取网页开头的部分
+const MAX_PAGE_BYTES: usize = 512 * 1024;
+const MAX_FAVICON_BYTES: usize = 64 * 1024;
+
+fn link_tag_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?is)<(?:link|meta)\b[^>]*>").unwrap())
+}
+
+fn attribute_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r#"(?is)([a-z:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+    })
+}
+
+// 整段文本只有一个 http(s) 网址时返回该网址
+fn as_link(text: &str) -> Option<Url> {
+    let text = text.trim();
+    if text.contains(char::is_whitespace) {
+        return None;
+    }
+    Url::parse(text)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    attribute_pattern().captures_iter(tag).find_map(|captures| {
+        captures[1].eq_ignore_ascii_case(name).then(|| {
+            let value = captures.get(2).or(captures.get(3)).or(captures.get(4));
+            decode_entities(value.map_or("", |value| value.as_str()))
+        })
+    })
+}
+
+// 优先使用 og:title，没有时使用 <title>
+fn find_title(html: &str) -> Option<String> {
+    let og_title = link_tag_pattern()
+        .find_iter(html)
+        .map(|tag| tag.as_str())
+        .find(|tag| attribute(tag, "property").is_some_and(|p| p.eq_ignore_ascii_case("og:title")))
+        .and_then(|tag| attribute(tag, "content"));
+    let title = og_title.or_else(|| {
+        title_pattern()
+            .captures(html)
+            .map(|captures| decode_entities(&captures[1]))
+    })?;
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+// 网页中声明的图标地址，没有声明时使用网站根目录的 favicon.ico
+fn find_icon(html: &str, base: &Url) -> Option<Url> {
+    let href = link_tag_pattern()
+        .find_iter(html)
+        .map(|tag| tag.as_str())
+        .find(|tag| {
+            attribute(tag, "rel").is_some_and(|rel| {
+                rel.split_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("icon"))
+            })
+        })
+        .and_then(|tag| attribute(tag, "href"));
+    base.join(href.as_deref().unwrap_or("/favicon.ico")).ok()
+}
+
+// 读取响应内容，超过 limit 时截断
+async fn read_limited(mut response: Response, limit: usize) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= limit {
+            body.truncate(limit);
+            break;
+        }
+    }
+    Ok(body)
+}
+
+fn content_type(response: &Response) -> String {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+            value
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_lowercase()
+        })
+        .unwrap_or_default()
+}
+
+// 下载图标并转换为 data URL，前端不需要再访问网络
+async fn fetch_favicon(client: &Client, url: Url) -> Option<String> {
+    let response = client
+        .get(url)
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+    let mime = content_type(&response);
+    if !mime.starts_with("image/") {
+        return None;
+    }
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_FAVICON_BYTES as u64)
+    {
+        return None;
+    }
+    let data = read_limited(response, MAX_FAVICON_BYTES + 1).await.ok()?;
+    (!data.is_empty() && data.len() <= MAX_FAVICON_BYTES)
+        .then(|| format!("data:{};base64,{}", mime, STANDARD.encode(data)))
+}
+
+async fn fetch_link<R: Runtime>(app: &AppHandle<R>, url: Url) -> Result<Option<Value>, String> {
+    let client = http_client(app).await?;
+    let response = client
+        .get(url)
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+    if content_type(&response) != "text/html" {
+        return Ok(None);
+    }
+    // 跟随跳转后的地址，相对的图标地址以它为基准
+    let base = response.url().clone();
+    let body = read_limited(response, MAX_PAGE_BYTES).await?;
+    let html = String::from_utf8_lossy(&body);
+
+    let title = find_title(&html);
+    let favicon = match find_icon(&html, &base) {
+        Some(icon) => fetch_favicon(&client, icon).await,
+        None => None,
+    };
+    if title.is_none() && favicon.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(json!({
+        "title": title,
+        "favicon": favicon,
+        "fetchedAt": now_millis(),
+    })))
+}
+
+fn save_link<R: Runtime>(app: &AppHandle<R>, id: i64, link: Value) -> Result<(), String> {
+    let item = {
+        let db = app.state::<Db>();
+        let conn = db.0.lock().unwrap();
+        update_metadata(&conn, id, "link", &link).map_err(|e| e.to_string())?;
+        get_item(&conn, id).map_err(|e| e.to_string())?
+    };
+    // 获取期间记录可能已被删除；锁定期间不向前端推送内容
+    if let Some(item) = item.filter(|_| !app.state::<AppLock>().is_locked()) {
+        if let Err(e) = app.emit("item-updated", item) {
+            println!("Link preview - Failed to emit event: {:?}", e);
+        }
+    }
+    Ok(())
+}
+
+// 新复制的网址在后台获取链接信息，由剪贴板监听在保存记录后调用
+// 敏感内容不联网获取，已经获取过的记录（重复复制）不再获取
+pub fn enrich_link<R: Runtime>(app: &AppHandle<R>, item: &ClipItem) {
+    if item.content_type != CONTENT_TEXT
+        || is_flagged(item.metadata.as_ref())
+        || item
+            .metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.get("link").is_some())
+        || !current_settings(app).capture.link_previews
+    {
+        return;
+    }
+    let Some(url) = as_link(&item.content) else {
+        return;
+    };
+
+    let app = app.clone();
+    let id = item.id;
+    tauri::async_runtime::spawn(async move {
+        match fetch_link(&app, url).await {
+            Ok(Some(link)) => {
+                if let Err(e) = save_link(&app, id, link) {
+                    println!("Link preview - Failed to save: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => println!("Link preview - Failed to fetch: {}", e),
+        }
+    });
+}
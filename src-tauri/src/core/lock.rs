@@ -0,0 +1,280 @@
+// core/lock.rs
+// 应用锁：设置锁定密码（argon2 哈希后保存在系统钥匙串）后，空闲一段时间或从托盘手动锁定
+// 锁定期间所有返回历史内容的命令都返回错误，托盘不显示最近记录，也不向前端推送新内容；可选暂停剪贴板监听
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::core::clipboard_watcher::{pause_watcher, resume_watcher};
+use crate::core::keystore::{delete_secret, get_secret, has_secret, set_secret, Secret};
+use crate::core::settings::current_settings;
+use crate::core::tray::refresh_tray_menu;
+
+// 检查是否空闲超时的间隔
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const SALT_LEN: usize = 16;
+// 连续输错这么多次之后开始限制尝试，等待时间从 1 秒起每次翻倍，最长 5 分钟
+const FREE_ATTEMPTS: u32 = 5;
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LockSettings {
+    // 空闲多少分钟后自动锁定，None 表示只手动锁定
+    pub idle_minutes: Option<u32>,
+    // 锁定期间暂停剪贴板监听
+    pub pause_watcher: bool,
+    // 查看含有密钥的记录原文时是否需要再次输入锁定密码
+    pub reveal_requires_passcode: bool,
+}
+
+impl Default for LockSettings {
+    fn default() -> Self {
+        Self {
+            idle_minutes: Some(15),
+            pause_watcher: false,
+            reveal_requires_passcode: false,
+        }
+    }
+}
+
+pub struct AppLock {
+    locked: AtomicBool,
+    // 是否设置了锁定密码，启动时从钥匙串读取
+    enabled: AtomicBool,
+    last_activity: Mutex<Instant>,
+    // 锁定时是否暂停了剪贴板监听，解锁时据此恢复
+    paused_watcher: AtomicBool,
+    attempts: Mutex<FailedAttempts>,
+}
+
+#[derive(Default)]
+struct FailedAttempts {
+    count: u32,
+    retry_after: Option<Instant>,
+}
+
+impl Default for AppLock {
+    fn default() -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            enabled: AtomicBool::new(false),
+            last_activity: Mutex::new(Instant::now()),
+            paused_watcher: AtomicBool::new(false),
+            attempts: Mutex::new(FailedAttempts::default()),
+        }
+    }
+}
+
+impl AppLock {
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    // 返回历史内容的命令需要先调用，锁定时返回错误，未锁定时记录一次活动
+    pub fn ensure_unlocked(&self) -> Result<(), String> {
+        if self.is_locked() {
+            return Err("应用已锁定，请先解锁".into());
+        }
+        self.touch();
+        Ok(())
+    }
+
+    fn touch(&self) {
+        *self.last_activity.lock().unwrap() = Instant::now();
+    }
+
+    // 所有需要输入锁定密码的地方都通过这里验证，连续输错后按退避时间拒绝尝试
+    // 验证期间持有计数的锁，并发的尝试也只能逐个进行
+    pub fn verify_passcode(&self, passcode: &str) -> Result<bool, String> {
+        let mut attempts = self.attempts.lock().unwrap();
+        if let Some(wait) = attempts
+            .retry_after
+            .and_then(|at| at.checked_duration_since(Instant::now()))
+        {
+            return Err(format!(
+                "密码错误次数过多，请 {} 秒后再试",
+                wait.as_secs() + 1
+            ));
+        }
+
+        let valid = verify_passcode(passcode)?;
+        if valid {
+            *attempts = FailedAttempts::default();
+        } else {
+            attempts.count += 1;
+            if attempts.count >= FREE_ATTEMPTS {
+                let backoff = Duration::from_secs(1 << (attempts.count - FREE_ATTEMPTS).min(16))
+                    .min(MAX_BACKOFF);
+                attempts.retry_after = Some(Instant::now() + backoff);
+                println!(
+                    "Lock - {} failed attempts, retry after {:?}",
+                    attempts.count, backoff
+                );
+            }
+        }
+        Ok(valid)
+    }
+}
+
+fn hash_passcode(passcode: &str) -> Result<String, String> {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::fill(&mut salt).map_err(|e| e.to_string())?;
+    let salt = SaltString::encode_b64(&salt).map_err(|e| e.to_string())?;
+    Argon2::default()
+        .hash_password(passcode.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn verify_passcode(passcode: &str) -> Result<bool, String> {
+    let hash = get_secret(Secret::LockPasscode)?.ok_or("尚未设置锁定密码")?;
+    let hash = PasswordHash::new(&hash).map_err(|e| e.to_string())?;
+    Ok(Argon2::default()
+        .verify_password(passcode.as_bytes(), &hash)
+        .is_ok())
+}
+
+fn lock<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<AppLock>();
+    if state.locked.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if current_settings(app).lock.pause_watcher && pause_watcher(app) {
+        state.paused_watcher.store(true, Ordering::SeqCst);
+    }
+    refresh_tray_menu(app);
+    println!("Lock - Locked");
+    if let Err(e) = app.emit("app-locked", true) {
+        println!("Lock - Failed to emit event: {:?}", e);
+    }
+}
+
+fn unlock<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<AppLock>();
+    if !state.locked.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    state.touch();
+    if state.paused_watcher.swap(false, Ordering::SeqCst) {
+        resume_watcher(app);
+    }
+    refresh_tray_menu(app);
+    println!("Lock - Unlocked");
+    if let Err(e) = app.emit("app-locked", false) {
+        println!("Lock - Failed to emit event: {:?}", e);
+    }
+}
+
+// 从托盘锁定，未设置密码时忽略
+pub fn lock_from_tray<R: Runtime>(app: &AppHandle<R>) {
+    if app.state::<AppLock>().is_enabled() {
+        lock(app);
+    }
+}
+
+// 设置了锁定密码时以锁定状态启动，并在后台检查空闲时间
+pub fn setup_lock<R: Runtime>(app: &AppHandle<R>) {
+    let enabled = has_secret(Secret::LockPasscode).unwrap_or_else(|e| {
+        println!("Lock - Failed to read passcode from keystore: {}", e);
+        false
+    });
+    app.state::<AppLock>()
+        .enabled
+        .store(enabled, Ordering::SeqCst);
+    if enabled {
+        lock(app);
+    }
+
+    let app_handle = app.clone();
+    thread::spawn(move || loop {
+        thread::sleep(IDLE_CHECK_INTERVAL);
+        let state = app_handle.state::<AppLock>();
+        if !state.is_enabled() || state.is_locked() {
+            continue;
+        }
+        let Some(minutes) = current_settings(&app_handle).lock.idle_minutes else {
+            continue;
+        };
+        let idle = state.last_activity.lock().unwrap().elapsed();
+        if idle >= Duration::from_secs(minutes as u64 * 60) {
+            lock(&app_handle);
+        }
+    });
+}
+
+// 设置、修改或移除锁定密码，已设置密码时需要提供当前密码；passcode 为空表示移除
+#[tauri::command]
+pub async fn set_passcode<R: Runtime>(
+    app: AppHandle<R>,
+    current: Option<String>,
+    passcode: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<AppLock>();
+    if state.is_enabled() && !state.verify_passcode(current.as_deref().unwrap_or_default())? {
+        return Err("当前密码错误".into());
+    }
+
+    match passcode.filter(|passcode| !passcode.is_empty()) {
+        Some(passcode) => {
+            set_secret(Secret::LockPasscode, &hash_passcode(&passcode)?)?;
+            state.enabled.store(true, Ordering::SeqCst);
+        }
+        None => {
+            delete_secret(Secret::LockPasscode)?;
+            state.enabled.store(false, Ordering::SeqCst);
+            unlock(&app);
+        }
+    }
+    refresh_tray_menu(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn has_passcode(state: State<'_, AppLock>) -> bool {
+    state.is_enabled()
+}
+
+#[tauri::command]
+pub fn lock_app<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    if !app.state::<AppLock>().is_enabled() {
+        return Err("尚未设置锁定密码".into());
+    }
+    lock(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unlock_app<R: Runtime>(app: AppHandle<R>, passcode: String) -> Result<(), String> {
+    if !app.state::<AppLock>().verify_passcode(&passcode)? {
+        return Err("密码错误".into());
+    }
+    unlock(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn is_app_locked(state: State<'_, AppLock>) -> bool {
+    state.is_locked()
+}
+
+// 前端在用户操作时调用，用于计算空闲时间
+#[tauri::command]
+pub fn report_activity(state: State<'_, AppLock>) {
+    state.touch();
+}
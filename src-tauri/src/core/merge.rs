@@ -0,0 +1,62 @@
+// core/merge.rs
+// 合并记录：把多条文本记录按用户选择的顺序拼接为一条新记录，可以同时复制到剪贴板
+use serde::Deserialize;
+use serde_json::json;
+use tauri::{AppHandle, Runtime};
+
+use crate::core::clipboard_watcher::{ingest_text, write_text};
+use crate::core::db::{ClipItem, CONTENT_TEXT};
+use crate::core::sensitive::{conceal, get_revealed_item};
+use crate::core::settings::current_settings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeSeparator {
+    #[default]
+    Newline,
+    Space,
+    Comma,
+}
+
+impl MergeSeparator {
+    fn as_str(self) -> &'static str {
+        match self {
+            MergeSeparator::Newline => "\n",
+            MergeSeparator::Space => " ",
+            MergeSeparator::Comma => ", ",
+        }
+    }
+}
+
+// 按 ids 的顺序合并，保存为新记录并返回；copy 为 true 时同时写入剪贴板
+#[tauri::command]
+pub fn merge_items<R: Runtime>(
+    app: AppHandle<R>,
+    ids: Vec<i64>,
+    separator: Option<MergeSeparator>,
+    copy: Option<bool>,
+    passcode: Option<String>,
+) -> Result<ClipItem, String> {
+    if ids.len() < 2 {
+        return Err("至少选择两条记录".into());
+    }
+
+    let mut parts = Vec::with_capacity(ids.len());
+    for &id in &ids {
+        let item = get_revealed_item(&app, id, passcode.as_deref())?;
+        if item.content_type != CONTENT_TEXT {
+            return Err("只能合并文本记录".into());
+        }
+        parts.push(item.content);
+    }
+
+    let merged = parts.join(separator.unwrap_or_default().as_str());
+    // 合并后的文本同样经过敏感内容识别，被拒绝保存时返回错误
+    let mut item = ingest_text(&app, &merged, Some(json!({ "merged": { "from": ids } })))
+        .ok_or("合并后的内容未被保存")?;
+    if copy.unwrap_or(false) {
+        write_text(&merged)?;
+    }
+    conceal(&mut item, &current_settings(&app).capture.sensitive);
+    Ok(item)
+}
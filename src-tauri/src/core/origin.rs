@@ -0,0 +1,84 @@
+// core/origin.rs
+// 文本来源：从来源窗口标题中尽量找出正在编辑的文件路径或网址，记录在元数据的 origin 字段
+// 很多编辑器会在标题中显示完整路径，浏览器一般不显示网址，找不到时不记录
+use serde_json::{json, Value};
+use std::path::Path;
+use tauri::{AppHandle, Runtime, State};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::core::db::{get_item, Db};
+use crate::core::lock::AppLock;
+use crate::core::source_app::SourceApp;
+
+// 窗口标题中常见的分隔符，例如 “main.rs - clipper - Visual Studio Code”
+const TITLE_SEPARATORS: &[&str] = &[" - ", " — ", " – ", " | "];
+
+// 去掉编辑器表示未保存的标记，例如 “*notes.txt” 或 “● main.rs”
+fn clean_segment(segment: &str) -> &str {
+    segment
+        .trim()
+        .trim_start_matches(['●', '*'])
+        .trim_end_matches('*')
+        .trim()
+}
+
+fn title_segments(title: &str) -> Vec<&str> {
+    let mut segments = vec![title];
+    for separator in TITLE_SEPARATORS {
+        segments = segments
+            .into_iter()
+            .flat_map(|segment| segment.split(separator))
+            .collect();
+    }
+    segments.into_iter().map(clean_segment).collect()
+}
+
+// 为来源应用生成 origin 元数据，标题中没有可用的路径或网址时返回 None
+pub fn origin_metadata(source: &SourceApp) -> Option<Value> {
+    title_segments(&source.title)
+        .into_iter()
+        .find_map(|segment| {
+            if segment.starts_with("http://") || segment.starts_with("https://") {
+                Some(json!({ "url": segment }))
+            } else if Path::new(segment).is_absolute() && Path::new(segment).exists() {
+                Some(json!({ "path": segment }))
+            } else {
+                None
+            }
+        })
+        .map(|mut origin| {
+            origin["app"] = json!(source.name);
+            json!({ "origin": origin })
+        })
+}
+
+// 用系统默认程序打开记录的来源文件或网址
+#[tauri::command]
+pub fn open_origin<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    id: i64,
+) -> Result<(), String> {
+    lock.ensure_unlocked()?;
+    let item = get_item(&db.0.lock().unwrap(), id)
+        .map_err(|e| e.to_string())?
+        .ok_or("记录不存在")?;
+    let origin = item
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("origin"))
+        .ok_or("该记录没有来源")?;
+
+    if let Some(path) = origin.get("path").and_then(Value::as_str) {
+        app.opener()
+            .open_path(path, None::<&str>)
+            .map_err(|e| e.to_string())
+    } else if let Some(url) = origin.get("url").and_then(Value::as_str) {
+        app.opener()
+            .open_url(url, None::<&str>)
+            .map_err(|e| e.to_string())
+    } else {
+        Err("该记录没有来源".into())
+    }
+}
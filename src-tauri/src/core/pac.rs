@@ -0,0 +1,329 @@
+// core/pac.rs
+// PAC 自动代理配置：在 QuickJS 中执行 PAC 脚本的 FindProxyForURL，为每个请求选择代理
+// 标准辅助函数（isInNet、shExpMatch、dateRange 等）用 JS 实现，DNS 解析和本机地址由 Rust 提供
+// 脚本下载后只编译一次，执行结果按 URL 缓存；dnsResolve 会阻塞，调用方需要在允许阻塞的线程上执行
+use rquickjs::{Context, Function, Runtime};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, ToSocketAddrs, UdpSocket},
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+// 单次执行脚本的时间和内存上限，防止脚本死循环或占满内存
+const EVAL_TIMEOUT: Duration = Duration::from_secs(2);
+const MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+// 执行结果的缓存时间和条数，脚本可能按时间（timeRange 等）或 DNS 结果选择代理
+const RESULT_TTL: Duration = Duration::from_secs(5 * 60);
+const MAX_RESULTS: usize = 256;
+
+const PAC_UTILS: &str = r#"
+var DAYS = ['SUN', 'MON', 'TUE', 'WED', 'THU', 'FRI', 'SAT'];
+var MONTHS = ['JAN', 'FEB', 'MAR', 'APR', 'MAY', 'JUN', 'JUL', 'AUG', 'SEP', 'OCT', 'NOV', 'DEC'];
+
+function isPlainHostName(host) {
+  return host.indexOf('.') < 0;
+}
+
+function dnsDomainIs(host, domain) {
+  return host.length >= domain.length && host.substring(host.length - domain.length) === domain;
+}
+
+function localHostOrDomainIs(host, hostdom) {
+  return host === hostdom || hostdom.lastIndexOf(host + '.', 0) === 0;
+}
+
+function isResolvable(host) {
+  return dnsResolve(host) !== null;
+}
+
+function dnsDomainLevels(host) {
+  return host.split('.').length - 1;
+}
+
+function ipToInt(ip) {
+  var parts = String(ip).split('.');
+  return ((parts[0] << 24) | (parts[1] << 16) | (parts[2] << 8) | parts[3]) >>> 0;
+}
+
+function isInNet(host, pattern, mask) {
+  var ip = /^\d+\.\d+\.\d+\.\d+$/.test(host) ? host : dnsResolve(host);
+  if (!ip) return false;
+  var m = ipToInt(mask);
+  return ((ipToInt(ip) & m) >>> 0) === ((ipToInt(pattern) & m) >>> 0);
+}
+
+function shExpMatch(str, shexp) {
+  var re = shexp.replace(/[.+^${}()|[\]\\]/g, '\\$&').replace(/\*/g, '.*').replace(/\?/g, '.');
+  return new RegExp('^' + re + '$').test(str);
+}
+
+function splitGmt(args) {
+  args = Array.prototype.slice.call(args);
+  var gmt = args[args.length - 1] === 'GMT';
+  if (gmt) args.pop();
+  return { args: args, gmt: gmt, now: new Date() };
+}
+
+function inRange(value, start, end) {
+  return start <= end ? value >= start && value <= end : value >= start || value <= end;
+}
+
+function weekdayRange() {
+  var c = splitGmt(arguments);
+  var today = c.gmt ? c.now.getUTCDay() : c.now.getDay();
+  var start = DAYS.indexOf(c.args[0]);
+  var end = c.args.length > 1 ? DAYS.indexOf(c.args[1]) : start;
+  return start >= 0 && end >= 0 && inRange(today, start, end);
+}
+
+function dateRange() {
+  var c = splitGmt(arguments);
+  var n = c.args.length;
+  if (n === 0 || (n > 1 && n % 2 === 1)) return false;
+  var parsed = c.args.map(function (arg) {
+    var month = MONTHS.indexOf(arg);
+    if (month >= 0) return { field: 'month', value: month };
+    var value = Number(arg);
+    return { field: value > 31 ? 'year' : 'day', value: value };
+  });
+  var start = n === 1 ? parsed : parsed.slice(0, n / 2);
+  var end = n === 1 ? parsed : parsed.slice(n / 2);
+  var today = {
+    day: c.gmt ? c.now.getUTCDate() : c.now.getDate(),
+    month: c.gmt ? c.now.getUTCMonth() : c.now.getMonth(),
+    year: c.gmt ? c.now.getUTCFullYear() : c.now.getFullYear()
+  };
+  var scale = { year: 10000, month: 100, day: 1 };
+  function key(fields) {
+    return fields.reduce(function (sum, f) { return sum + f.value * scale[f.field]; }, 0);
+  }
+  var now = key(start.map(function (f) { return { field: f.field, value: today[f.field] }; }));
+  var hasYear = start.some(function (f) { return f.field === 'year'; });
+  return hasYear ? now >= key(start) && now <= key(end) : inRange(now, key(start), key(end));
+}
+
+function timeRange() {
+  var c = splitGmt(arguments);
+  var a = c.args.map(Number);
+  var now = (c.gmt ? c.now.getUTCHours() : c.now.getHours()) * 3600
+    + (c.gmt ? c.now.getUTCMinutes() : c.now.getMinutes()) * 60
+    + (c.gmt ? c.now.getUTCSeconds() : c.now.getSeconds());
+  var start, end;
+  switch (a.length) {
+    case 1: start = a[0] * 3600; end = start + 3599; break;
+    case 2: start = a[0] * 3600; end = a[1] * 3600; break;
+    case 4: start = a[0] * 3600 + a[1] * 60; end = a[2] * 3600 + a[3] * 60; break;
+    case 6: start = a[0] * 3600 + a[1] * 60 + a[2]; end = a[3] * 3600 + a[4] * 60 + a[5]; break;
+    default: return false;
+  }
+  return inRange(now, start, end);
+}
+"#;
+
+fn dns_resolve(host: String) -> Option<String> {
+    (host.as_str(), 0)
+        .to_socket_addrs()
+        .ok()?
+        .find(|addr| addr.is_ipv4())
+        .map(|addr| addr.ip().to_string())
+}
+
+// 本机对外使用的地址：UDP 套接字 connect 只选择路由，不发送数据
+fn my_ip_address() -> String {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("8.8.8.8:53")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .ok()
+        .filter(|ip| !ip.is_unspecified())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        .to_string()
+}
+
+// 编译后的 PAC 脚本，脚本内容变化时重新编译
+pub struct PacScript {
+    context: Context,
+    // 本次执行的开始时间，超时后中断脚本
+    started: Arc<Mutex<Instant>>,
+    results: Mutex<HashMap<String, CachedResult>>,
+}
+
+struct CachedResult {
+    evaluated: Instant,
+    result: Result<String, String>,
+}
+
+impl PacScript {
+    // 执行脚本并对本机地址调用一次 FindProxyForURL，脚本有语法错误或没有定义 FindProxyForURL 时返回错误
+    pub fn compile(script: &str) -> Result<Self, String> {
+        let runtime = Runtime::new().map_err(|e| e.to_string())?;
+        runtime.set_memory_limit(MEMORY_LIMIT);
+        let started = Arc::new(Mutex::new(Instant::now()));
+        let deadline = started.clone();
+        runtime.set_interrupt_handler(Some(Box::new(move || {
+            deadline.lock().unwrap().elapsed() > EVAL_TIMEOUT
+        })));
+        let context = Context::full(&runtime).map_err(|e| e.to_string())?;
+
+        // 本机地址在脚本第一次调用 myIpAddress 时获取
+        let my_ip = Arc::new(OnceLock::new());
+        context
+            .with(|ctx| {
+                let globals = ctx.globals();
+                globals.set("dnsResolve", Function::new(ctx.clone(), dns_resolve)?)?;
+                globals.set(
+                    "myIpAddress",
+                    Function::new(ctx.clone(), move || {
+                        my_ip.get_or_init(my_ip_address).clone()
+                    })?,
+                )?;
+                ctx.eval::<(), _>(PAC_UTILS)?;
+                ctx.eval::<(), _>(script)
+            })
+            .map_err(|e| format!("PAC 脚本执行失败: {}", e))?;
+
+        let compiled = PacScript {
+            context,
+            started,
+            results: Mutex::new(HashMap::new()),
+        };
+        compiled.evaluate("http://localhost/", "localhost")?;
+        Ok(compiled)
+    }
+
+    // 缓存中未过期的结果
+    pub fn cached(&self, url: &str) -> Option<Result<String, String>> {
+        self.results
+            .lock()
+            .unwrap()
+            .get(url)
+            .filter(|cached| cached.evaluated.elapsed() < RESULT_TTL)
+            .map(|cached| cached.result.clone())
+    }
+
+    // 执行 FindProxyForURL，返回脚本的原始结果，例如 "PROXY proxy.corp.local:8080; DIRECT"
+    // 结果（包括执行出错）按 url 缓存
+    pub fn find_proxy_for_url(&self, url: &str, host: &str) -> Result<String, String> {
+        if let Some(result) = self.cached(url) {
+            return result;
+        }
+        let result = self.evaluate(url, host);
+        let mut results = self.results.lock().unwrap();
+        if results.len() >= MAX_RESULTS {
+            results.clear();
+        }
+        results.insert(
+            url.to_string(),
+            CachedResult {
+                evaluated: Instant::now(),
+                result: result.clone(),
+            },
+        );
+        result
+    }
+
+    fn evaluate(&self, url: &str, host: &str) -> Result<String, String> {
+        *self.started.lock().unwrap() = Instant::now();
+        self.context
+            .with(|ctx| {
+                let find: Function = ctx.globals().get("FindProxyForURL")?;
+                find.call::<_, String>((url, host))
+            })
+            .map_err(|e| format!("PAC 脚本执行失败: {}", e))
+    }
+}
+
+// 从脚本结果中选出第一个可用的代理地址，遇到 DIRECT 或没有可用代理时返回 None
+// reqwest 未启用 SOCKS 支持，SOCKS 代理会被跳过
+pub fn first_proxy(result: &str) -> Option<String> {
+    for entry in result.split(';') {
+        let mut parts = entry.split_whitespace();
+        let Some(kind) = parts.next() else {
+            continue;
+        };
+        match (kind.to_ascii_uppercase().as_str(), parts.next()) {
+            ("DIRECT", _) => return None,
+            ("PROXY" | "HTTP", Some(addr)) => return Some(format!("http://{}", addr)),
+            ("HTTPS", Some(addr)) => return Some(format!("https://{}", addr)),
+            _ => continue,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 只使用 IP 地址和不需要解析的主机名，测试不依赖网络和 DNS
+    const SCRIPT: &str = r#"
+        function FindProxyForURL(url, host) {
+            if (isPlainHostName(host) || dnsDomainIs(host, ".corp.local")) return "DIRECT";
+            if (shExpMatch(url, "https://*.example.com/*")) return "HTTPS secure:443";
+            if (isInNet(host, "10.0.0.0", "255.0.0.0")) return "SOCKS5 socks:1080; PROXY inner:3128";
+            return "PROXY proxy.corp.local:8080; DIRECT";
+        }
+    "#;
+
+    fn resolve(script: &PacScript, url: &str, host: &str) -> Option<String> {
+        first_proxy(&script.find_proxy_for_url(url, host).unwrap())
+    }
+
+    #[test]
+    fn evaluates_find_proxy_for_url() {
+        let script = PacScript::compile(SCRIPT).unwrap();
+        assert_eq!(resolve(&script, "http://intranet/", "intranet"), None);
+        assert_eq!(
+            resolve(&script, "http://wiki.corp.local/", "wiki.corp.local"),
+            None
+        );
+        assert_eq!(
+            resolve(&script, "http://10.1.2.3/", "10.1.2.3").as_deref(),
+            Some("http://inner:3128")
+        );
+        assert_eq!(
+            resolve(&script, "https://api.example.com/", "api.example.com").as_deref(),
+            Some("https://secure:443")
+        );
+        assert_eq!(
+            resolve(&script, "http://192.168.1.20/", "192.168.1.20").as_deref(),
+            Some("http://proxy.corp.local:8080")
+        );
+    }
+
+    #[test]
+    fn results_are_cached_per_url() {
+        let script = PacScript::compile(SCRIPT).unwrap();
+        assert!(script.cached("http://10.1.2.3/").is_none());
+        script
+            .find_proxy_for_url("http://10.1.2.3/", "10.1.2.3")
+            .unwrap();
+        assert_eq!(
+            script.cached("http://10.1.2.3/").unwrap().as_deref(),
+            Ok("SOCKS5 socks:1080; PROXY inner:3128")
+        );
+    }
+
+    #[test]
+    fn parses_proxy_list() {
+        assert_eq!(first_proxy("DIRECT"), None);
+        assert_eq!(first_proxy(""), None);
+        assert_eq!(first_proxy("SOCKS s:1080"), None);
+        assert_eq!(
+            first_proxy(" ; proxy p:80;DIRECT").as_deref(),
+            Some("http://p:80")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_scripts() {
+        let compile = |script: &str| PacScript::compile(script).map(|_| ());
+        assert!(compile("function FindProxyForURL(url, host) {").is_err());
+        assert!(compile("var x = 1;").is_err());
+        assert!(compile("function FindProxyForURL(url, host) { while (true) {} }").is_err());
+        assert!(compile("function FindProxyForURL() { return 'DIRECT'; }").is_ok());
+    }
+}
@@ -0,0 +1,227 @@
+// core/paster.rs
+// 选中即粘贴：把历史记录写入剪贴板，隐藏窗口让焦点回到之前的应用，再模拟 Ctrl+V / Cmd+V
+// 以纯文本粘贴时只写入 text/plain，去掉网页、文档带来的格式
+// 粘贴片段时可以在粘贴后把光标左移到片段中 {cursor} 的位置
+// 连续粘贴：先把多条记录依次加入队列，之后每次粘贴（命令或快捷键）按加入的顺序取出下一条
+use clipboard_rs::{Clipboard, ClipboardContext};
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::{collections::VecDeque, sync::Mutex, thread, time::Duration};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::core::clipboard_watcher::{write_item, write_text};
+use crate::core::db::{get_item, Db, CONTENT_TEXT};
+use crate::core::lock::AppLock;
+use crate::core::sensitive::get_revealed_item;
+
+// 隐藏窗口后等待系统把焦点交还给之前的应用，过早发送按键会落到 Clipper 自己身上
+const FOCUS_DELAY: Duration = Duration::from_millis(150);
+
+// 连续粘贴队列中的记录 id
+#[derive(Default)]
+pub struct PasteStack {
+    queue: Mutex<VecDeque<i64>>,
+}
+
+#[cfg(target_os = "macos")]
+const PASTE_MODIFIER: Key = Key::Meta;
+#[cfg(not(target_os = "macos"))]
+const PASTE_MODIFIER: Key = Key::Control;
+
+// 隐藏 Clipper，让系统激活之前的前台应用
+// Windows/Linux 隐藏窗口后会激活下一个窗口；macOS 需要隐藏整个应用才会切回之前的应用
+fn restore_previous_focus<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = window.hide() {
+            println!("Paster - Failed to hide window: {:?}", e);
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Err(e) = app.hide() {
+        println!("Paster - Failed to hide app: {:?}", e);
+    }
+}
+
+fn send_paste() -> Result<(), String> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+    enigo
+        .key(PASTE_MODIFIER, Direction::Press)
+        .map_err(|e| e.to_string())?;
+    let pasted = enigo.key(Key::Unicode('v'), Direction::Click);
+    // 无论粘贴是否成功都要松开修饰键，避免修饰键卡在按下状态
+    enigo
+        .key(PASTE_MODIFIER, Direction::Release)
+        .map_err(|e| e.to_string())?;
+    pasted.map_err(|e| e.to_string())
+}
+
+fn move_cursor_left(count: usize) -> Result<(), String> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+    for _ in 0..count {
+        enigo
+            .key(Key::LeftArrow, Direction::Click)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// 在后台线程中等待焦点切换后模拟粘贴，不阻塞主线程处理窗口隐藏
+// cursor_back 为粘贴后光标需要左移的字符数
+fn send_paste_later(cursor_back: usize) {
+    thread::spawn(move || {
+        thread::sleep(FOCUS_DELAY);
+        if let Err(e) = send_paste() {
+            println!("Paster - Failed to send paste keystroke: {}", e);
+            return;
+        }
+        if cursor_back > 0 {
+            // 等待目标应用处理完粘贴再移动光标
+            thread::sleep(FOCUS_DELAY);
+            if let Err(e) = move_cursor_left(cursor_back) {
+                println!("Paster - Failed to move cursor: {}", e);
+            }
+        }
+    });
+}
+
+// 将历史记录粘贴到之前的前台应用，主窗口和托盘菜单共用
+// 托盘和快捷键无法输入密码，查看密钥需要密码时不能从这两处粘贴含有密钥的记录
+pub fn paste_entry<R: Runtime>(
+    app: &AppHandle<R>,
+    id: i64,
+    strip_metadata: Option<bool>,
+    passcode: Option<&str>,
+) -> Result<(), String> {
+    let item = get_revealed_item(app, id, passcode)?;
+    write_item(app, &item, strip_metadata)?;
+    restore_previous_focus(app);
+    send_paste_later(0);
+    Ok(())
+}
+
+// 只写入纯文本后粘贴，不带保存的 HTML、RTF 格式
+pub fn paste_plain_entry<R: Runtime>(
+    app: &AppHandle<R>,
+    id: i64,
+    passcode: Option<&str>,
+) -> Result<(), String> {
+    let item = get_revealed_item(app, id, passcode)?;
+    if item.content_type != CONTENT_TEXT {
+        return Err("只能以纯文本粘贴文本记录".into());
+    }
+    write_text(&item.content)?;
+    restore_previous_focus(app);
+    send_paste_later(0);
+    Ok(())
+}
+
+// 粘贴生成的文本（例如展开后的片段），粘贴后光标左移 cursor_back 个字符
+pub fn paste_text<R: Runtime>(
+    app: &AppHandle<R>,
+    text: &str,
+    cursor_back: usize,
+) -> Result<(), String> {
+    write_text(text)?;
+    restore_previous_focus(app);
+    send_paste_later(cursor_back);
+    Ok(())
+}
+
+// 纯文本粘贴快捷键：去掉当前剪贴板内容的格式后粘贴到前台应用
+// 快捷键在目标应用中按下，焦点不需要切换，等待的时间用于松开快捷键的修饰键
+pub fn paste_clipboard_plain() {
+    let text = ClipboardContext::new()
+        .and_then(|ctx| ctx.get_text())
+        .map_err(|e| e.to_string());
+    match text {
+        Ok(text) if !text.is_empty() => match write_text(&text) {
+            Ok(()) => send_paste_later(0),
+            Err(e) => println!("Paster - Failed to write plain text: {}", e),
+        },
+        Ok(_) => {}
+        Err(e) => println!("Paster - Failed to read clipboard: {}", e),
+    }
+}
+
+// 队列变化后发送 paste-stack-changed 事件，负载为队列中的记录 id
+fn emit_stack<R: Runtime>(app: &AppHandle<R>, queue: &VecDeque<i64>) {
+    if let Err(e) = app.emit("paste-stack-changed", queue) {
+        println!("Paster - Failed to emit event: {:?}", e);
+    }
+}
+
+// 取出队列中的下一条并粘贴，队列为空时返回 false
+pub fn paste_next_entry<R: Runtime>(app: &AppHandle<R>) -> Result<bool, String> {
+    let stack = app.state::<PasteStack>();
+    let next = {
+        let mut queue = stack.queue.lock().unwrap();
+        let next = queue.pop_front();
+        if next.is_some() {
+            emit_stack(app, &queue);
+        }
+        next
+    };
+    match next {
+        Some(id) => paste_entry(app, id, None, None).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+pub fn paste_item<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    strip_metadata: Option<bool>,
+    passcode: Option<String>,
+) -> Result<(), String> {
+    paste_entry(&app, id, strip_metadata, passcode.as_deref())
+}
+
+#[tauri::command]
+pub fn paste_plain<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    passcode: Option<String>,
+) -> Result<(), String> {
+    paste_plain_entry(&app, id, passcode.as_deref())
+}
+
+// 把记录加入连续粘贴队列末尾，返回加入后的队列
+#[tauri::command]
+pub fn stack_push<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    stack: State<'_, PasteStack>,
+    id: i64,
+) -> Result<Vec<i64>, String> {
+    lock.ensure_unlocked()?;
+    if get_item(&db.0.lock().unwrap(), id)
+        .map_err(|e| e.to_string())?
+        .is_none()
+    {
+        return Err("记录不存在".into());
+    }
+    let mut queue = stack.queue.lock().unwrap();
+    queue.push_back(id);
+    emit_stack(&app, &queue);
+    Ok(queue.iter().copied().collect())
+}
+
+#[tauri::command]
+pub fn stack_clear<R: Runtime>(app: AppHandle<R>, stack: State<'_, PasteStack>) {
+    let mut queue = stack.queue.lock().unwrap();
+    queue.clear();
+    emit_stack(&app, &queue);
+}
+
+#[tauri::command]
+pub fn get_stack(stack: State<'_, PasteStack>) -> Vec<i64> {
+    stack.queue.lock().unwrap().iter().copied().collect()
+}
+
+// 粘贴队列中的下一条，队列为空时返回 false
+#[tauri::command]
+pub fn stack_paste_next<R: Runtime>(app: AppHandle<R>) -> Result<bool, String> {
+    paste_next_entry(&app)
+}
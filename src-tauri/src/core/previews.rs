@@ -0,0 +1,109 @@
+// core/previews.rs
+// 文件预览：为文件列表中的图片和 PDF 生成缩略图，缓存在应用数据目录的 previews 子目录
+// PDF 首页预览依赖 pdfium 动态库，需要启用 pdf-preview 特性
+use image::DynamicImage;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::core::db::{content_hash, get_item, Db, CONTENT_FILES};
+use crate::core::lock::AppLock;
+
+const PREVIEWS_DIR: &str = "previews";
+const THUMBNAIL_SIZE: u32 = 256;
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp"];
+
+fn previews_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(PREVIEWS_DIR);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+pub fn clear_previews<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(dir) = previews_dir(app) {
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            println!("Failed to clear previews: {:?}", e);
+        }
+    }
+}
+
+#[cfg(feature = "pdf-preview")]
+fn render_pdf(path: &Path) -> Result<DynamicImage, String> {
+    use pdfium_render::prelude::*;
+
+    let bindings = Pdfium::bind_to_system_library().map_err(|e| e.to_string())?;
+    let pdfium = Pdfium::new(bindings);
+    let document = pdfium
+        .load_pdf_from_file(path, None)
+        .map_err(|e| e.to_string())?;
+    let page = document.pages().get(0).map_err(|e| e.to_string())?;
+    let config = PdfRenderConfig::new()
+        .set_target_width(THUMBNAIL_SIZE as Pixels)
+        .set_maximum_height(THUMBNAIL_SIZE as Pixels);
+    let bitmap = page
+        .render_with_config(&config)
+        .map_err(|e| e.to_string())?;
+    Ok(bitmap.as_image())
+}
+
+#[cfg(not(feature = "pdf-preview"))]
+fn render_pdf(_path: &Path) -> Result<DynamicImage, String> {
+    Err("未启用 PDF 预览".into())
+}
+
+// 生成缩略图，不支持预览的文件类型返回 None
+fn render(path: &Path) -> Result<Option<DynamicImage>, String> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+
+    match extension.as_deref() {
+        Some(ext) if IMAGE_EXTENSIONS.contains(&ext) => image::open(path)
+            .map(|image| Some(image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)))
+            .map_err(|e| e.to_string()),
+        Some("pdf") => render_pdf(path).map(Some),
+        _ => Ok(None),
+    }
+}
+
+// 返回文件列表记录中第 index 个文件的缩略图路径，不支持预览时返回 None
+#[tauri::command]
+pub fn get_file_preview<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    id: i64,
+    index: usize,
+) -> Result<Option<String>, String> {
+    lock.ensure_unlocked()?;
+    let item = get_item(&db.0.lock().unwrap(), id)
+        .map_err(|e| e.to_string())?
+        .ok_or("记录不存在")?;
+    if item.content_type != CONTENT_FILES {
+        return Err("该记录不是文件列表".into());
+    }
+
+    let files: Vec<String> = serde_json::from_str(&item.content).map_err(|e| e.to_string())?;
+    let path = Path::new(files.get(index).ok_or("文件不存在")?);
+
+    // 缓存键包含文件修改时间，原文件变化后重新生成
+    let modified = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .map_err(|e| e.to_string())?;
+    let key = content_hash(format!("{}:{:?}", path.display(), modified).as_bytes());
+    let preview = previews_dir(&app)?.join(format!("{}.png", key));
+
+    if !preview.exists() {
+        let Some(thumbnail) = render(path)? else {
+            return Ok(None);
+        };
+        thumbnail.save(&preview).map_err(|e| e.to_string())?;
+    }
+    Ok(Some(preview.to_string_lossy().into_owned()))
+}
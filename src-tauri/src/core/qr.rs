@@ -0,0 +1,41 @@
+// core/qr.rs
+// 二维码：把文本记录生成二维码图片，手机扫码即可获取短文本或链接
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::{types::QrError, QrCode};
+use std::io::Cursor;
+use tauri::{AppHandle, Runtime};
+
+use crate::core::db::CONTENT_TEXT;
+use crate::core::sensitive::get_revealed_item;
+
+// 生成的图片最小边长，方便在屏幕上扫码
+const QR_MIN_SIZE: u32 = 320;
+
+// 返回二维码 PNG 的 data URL，前端可以直接显示
+#[tauri::command]
+pub fn generate_qr<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    passcode: Option<String>,
+) -> Result<String, String> {
+    let item = get_revealed_item(&app, id, passcode.as_deref())?;
+    if item.content_type != CONTENT_TEXT {
+        return Err("只能为文本记录生成二维码".into());
+    }
+
+    let code = QrCode::new(item.content.as_bytes()).map_err(|e| match e {
+        QrError::DataTooLong => "内容太长，无法生成二维码".to_string(),
+        e => e.to_string(),
+    })?;
+    let image = code
+        .render::<Luma<u8>>()
+        .min_dimensions(QR_MIN_SIZE, QR_MIN_SIZE)
+        .build();
+
+    let mut png = Vec::new();
+    DynamicImage::ImageLuma8(image)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(format!("data:image/png;base64,{}", STANDARD.encode(png)))
+}
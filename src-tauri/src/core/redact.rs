@@ -0,0 +1,131 @@
+// core/redact.rs
+// 脱敏：把记录中的邮箱、电话号码、银行卡号和自定义正则匹配的内容遮盖，生成可以分享的副本
+// 遮盖时保留分隔符，只替换字母和数字，可以保留末尾几位（例如银行卡后四位）
+use regex::Regex;
+use serde::Deserialize;
+use std::{ops::RangeInclusive, sync::OnceLock};
+use tauri::{AppHandle, Runtime};
+
+use crate::core::clipboard_watcher::write_text;
+use crate::core::db::CONTENT_TEXT;
+use crate::core::sensitive::{get_revealed_item, luhn_valid};
+
+const MASK_CHAR: char = '•';
+// 电话号码的位数范围（E.164 最多 15 位）
+const PHONE_DIGITS: RangeInclusive<usize> = 7..=15;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RedactPattern {
+    Email,
+    Phone,
+    CreditCard,
+    Custom { pattern: String },
+}
+
+fn email_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+").unwrap())
+}
+
+fn phone_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\+?\(?\d[\d\s().-]{5,}\d").unwrap())
+}
+
+fn card_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").unwrap())
+}
+
+fn digit_count(text: &str) -> usize {
+    text.chars().filter(char::is_ascii_digit).count()
+}
+
+// 遮盖字母和数字，保留最后 keep_last 个
+fn mask(text: &str, keep_last: usize) -> String {
+    let total = text.chars().filter(|ch| ch.is_alphanumeric()).count();
+    let mut seen = 0;
+    text.chars()
+        .map(|ch| {
+            if !ch.is_alphanumeric() {
+                return ch;
+            }
+            seen += 1;
+            if seen + keep_last > total {
+                ch
+            } else {
+                MASK_CHAR
+            }
+        })
+        .collect()
+}
+
+// 遮盖 regex 匹配且通过 accept 检查的部分
+fn mask_matches(
+    text: &str,
+    regex: &Regex,
+    keep_last: usize,
+    accept: impl Fn(&str) -> bool,
+) -> String {
+    regex
+        .replace_all(text, |captures: &regex::Captures| {
+            let matched = &captures[0];
+            if accept(matched) {
+                mask(matched, keep_last)
+            } else {
+                matched.to_string()
+            }
+        })
+        .into_owned()
+}
+
+pub fn redact(text: &str, patterns: &[RedactPattern], keep_last: usize) -> Result<String, String> {
+    // 银行卡号先于电话号码处理，避免被当作电话号码遮盖后无法保留后四位
+    let mut ordered: Vec<&RedactPattern> = patterns.iter().collect();
+    ordered.sort_by_key(|pattern| !matches!(pattern, RedactPattern::CreditCard));
+
+    let mut redacted = text.to_string();
+    for pattern in ordered {
+        redacted = match pattern {
+            RedactPattern::Email => mask_matches(&redacted, email_pattern(), keep_last, |_| true),
+            RedactPattern::Phone => {
+                mask_matches(&redacted, phone_pattern(), keep_last, |matched| {
+                    PHONE_DIGITS.contains(&digit_count(matched))
+                })
+            }
+            RedactPattern::CreditCard => {
+                mask_matches(&redacted, card_pattern(), keep_last, |matched| {
+                    let digits: Vec<u32> =
+                        matched.chars().filter_map(|ch| ch.to_digit(10)).collect();
+                    luhn_valid(&digits)
+                })
+            }
+            RedactPattern::Custom { pattern } => {
+                let regex = Regex::new(pattern).map_err(|e| format!("正则表达式无效: {}", e))?;
+                mask_matches(&redacted, &regex, keep_last, |_| true)
+            }
+        };
+    }
+    Ok(redacted)
+}
+
+// 生成记录的脱敏副本，写入剪贴板并返回，原记录不变
+// keep_last 为每处遮盖保留的末尾字母或数字个数，例如 4 表示保留银行卡后四位
+#[tauri::command]
+pub fn redact_entry<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    patterns: Vec<RedactPattern>,
+    keep_last: Option<usize>,
+    passcode: Option<String>,
+) -> Result<String, String> {
+    let item = get_revealed_item(&app, id, passcode.as_deref())?;
+    if item.content_type != CONTENT_TEXT {
+        return Err("只能脱敏文本记录".into());
+    }
+
+    let redacted = redact(&item.content, &patterns, keep_last.unwrap_or(0))?;
+    write_text(&redacted)?;
+    Ok(redacted)
+}
@@ -0,0 +1,678 @@
+// core/retention.rs
+// 历史保留策略：按条数、保存天数和占用空间清理旧记录，固定的记录不会被清理也不计入限制
+// 设置了到期时间的记录（例如敏感内容）到期后也在这里删除
+// 每次写入新记录后清理主数据库中到期、按标签、天数、条数和占用空间超出限制的记录；
+// 归档的分片（见 shards.rs）只在定时清理、修改策略和手动清理时清理，这时也把本月之前的记录归档
+// 条数和空间限制包括分片中的记录，额度先给主数据库，再按月份从新到旧分给各个分片
+// 按标签的保留规则优先于全局策略：带有规则中任一标签的记录只按标签规则的保留时间清理，
+// 不计入全局的条数、天数和空间限制；匹配多条规则时按保留时间最长的一条，其中有永久保留的就不清理
+// 固定的记录和到期时间不受标签规则影响
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::core::db::{now_millis, referenced_images, Db, CONTENT_IMAGE};
+use crate::core::images::{images_dir, remove_unreferenced_images};
+use crate::core::settings::{current_settings, update};
+use crate::core::shards::{archive, list_shards, remove_shard, with_shard};
+use crate::core::tags::normalize_tag;
+use crate::core::tray::refresh_tray_menu;
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const MINUTE_MILLIS: i64 = 60 * 1000;
+const DAY_MILLIS: i64 = 24 * 60 * MINUTE_MILLIS;
+const MB: u64 = 1024 * 1024;
+
+// 下次定时清理的时间（毫秒），0 表示尚未启动，供 get_next_run_times 查看
+static NEXT_CLEANUP: AtomicI64 = AtomicI64::new(0);
+// 最早的到期时间（毫秒），定时清理线程在这个时间醒来删除到期的记录；更早的到期时间通过条件变量唤醒它
+static NEXT_EXPIRY: Mutex<Option<i64>> = Mutex::new(None);
+static EXPIRY_CHANGED: Condvar = Condvar::new();
+
+// 各项限制为 None 时不限制
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionPolicy {
+    pub max_items: Option<u32>,
+    pub max_age_days: Option<u32>,
+    // 文本按内容字节数计算，图片按文件大小计算
+    pub max_total_mb: Option<u64>,
+    pub tag_rules: Vec<TagRetention>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_items: Some(1000),
+            max_age_days: None,
+            max_total_mb: None,
+            tag_rules: Vec::new(),
+        }
+    }
+}
+
+// 按标签设置的保留时间，例如 receipts 永久保留，otp 五分钟后删除
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRetention {
+    pub tag: String,
+    // 最近使用后保留多少分钟，None 表示永久保留
+    pub max_age_minutes: Option<u64>,
+}
+
+// 保存保留策略前检查标签规则，同一个标签只能有一条规则
+pub fn validate_retention(policy: &RetentionPolicy) -> Result<(), String> {
+    let mut tags = HashSet::new();
+    for rule in &policy.tag_rules {
+        let tag = normalize_tag(&rule.tag)?;
+        if !tags.insert(tag) {
+            return Err(format!("标签 {} 有多条保留规则", rule.tag.trim()));
+        }
+    }
+    Ok(())
+}
+
+// 标签规则中的标签，作为 JSON 数组传给 SQL 的 json_each
+fn ruled_tags(policy: &RetentionPolicy) -> String {
+    let tags: Vec<String> = policy
+        .tag_rules
+        .iter()
+        .filter_map(|rule| normalize_tag(&rule.tag).ok())
+        .collect();
+    serde_json::to_string(&tags).unwrap_or_else(|_| "[]".into())
+}
+
+// 删除已到期的记录，固定的记录除外
+fn prune_expired(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM items WHERE pinned = 0 AND expires_at <= ?1",
+        params![now_millis()],
+    )
+}
+
+// 按标签规则删除 schema 中超过保留时间的记录，schema 为 main 或附加的 shard
+fn prune_by_tags(
+    conn: &Connection,
+    schema: &str,
+    rules: &[TagRetention],
+) -> rusqlite::Result<usize> {
+    let rules: HashMap<String, Option<u64>> = rules
+        .iter()
+        .filter_map(|rule| Some((normalize_tag(&rule.tag).ok()?, rule.max_age_minutes)))
+        .collect();
+    if rules.is_empty() {
+        return Ok(0);
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT items.id, items.last_used, item_tags.tag
+         FROM {schema}.items AS items JOIN {schema}.item_tags AS item_tags ON item_tags.item_id = items.id
+         WHERE items.pinned = 0",
+        schema = schema
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>("id")?,
+            row.get::<_, i64>("last_used")?,
+            row.get::<_, String>("tag")?,
+        ))
+    })?;
+
+    // 每条记录取匹配规则中最长的保留时间，None 表示永久保留
+    let mut retained: HashMap<i64, (i64, Option<u64>)> = HashMap::new();
+    for row in rows {
+        let (id, last_used, tag) = row?;
+        let Some(&max_age) = rules.get(&tag) else {
+            continue;
+        };
+        retained
+            .entry(id)
+            .and_modify(|(_, longest)| {
+                *longest = longest.zip(max_age).map(|(a, b)| a.max(b));
+            })
+            .or_insert((last_used, max_age));
+    }
+
+    let now = now_millis();
+    let mut delete = conn.prepare(&format!("DELETE FROM {}.items WHERE id = ?1", schema))?;
+    let mut removed = 0;
+    for (id, (last_used, max_age)) in retained {
+        if let Some(minutes) = max_age {
+            if last_used < now - minutes as i64 * MINUTE_MILLIS {
+                removed += delete.execute(params![id])?;
+            }
+        }
+    }
+    Ok(removed)
+}
+
+// 全局限制剩余的额度，先由主数据库使用，再按月份从新到旧分给各个分片
+struct Budget {
+    items: Option<u32>,
+    bytes: Option<u64>,
+    // 已经计算过大小的图片文件，多条记录引用同一个文件时只计算一次
+    counted_images: HashSet<String>,
+}
+
+// 以下全局策略都跳过带有标签规则的记录，ruled 为 ruled_tags 的结果
+// schema 为 main 或附加的 shard
+fn prune_by_age(
+    conn: &Connection,
+    schema: &str,
+    days: u32,
+    ruled: &str,
+) -> rusqlite::Result<usize> {
+    let cutoff = now_millis() - days as i64 * DAY_MILLIS;
+    conn.execute(
+        &format!(
+            "DELETE FROM {schema}.items WHERE pinned = 0 AND last_used < ?1
+             AND id NOT IN (SELECT item_id FROM {schema}.item_tags
+                WHERE tag IN (SELECT value FROM json_each(?2)))",
+            schema = schema
+        ),
+        params![cutoff, ruled],
+    )
+}
+
+// 最多保留 max_items 条，返回删除的记录数和保留的记录数
+fn prune_by_count(
+    conn: &Connection,
+    schema: &str,
+    max_items: u32,
+    ruled: &str,
+) -> rusqlite::Result<(usize, u32)> {
+    let candidates = format!(
+        "SELECT id, last_used FROM {schema}.items WHERE pinned = 0
+         AND id NOT IN (SELECT item_id FROM {schema}.item_tags
+            WHERE tag IN (SELECT value FROM json_each(?1)))",
+        schema = schema
+    );
+    let count: u32 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM ({})", candidates),
+        params![ruled],
+        |row| row.get(0),
+    )?;
+    if count <= max_items {
+        return Ok((0, count));
+    }
+    let removed = conn.execute(
+        &format!(
+            "WITH candidates AS ({candidates})
+            DELETE FROM {schema}.items WHERE id IN (SELECT id FROM candidates) AND id NOT IN (
+                SELECT id FROM candidates ORDER BY last_used DESC, id DESC LIMIT ?2
+            )",
+            candidates = candidates,
+            schema = schema
+        ),
+        params![ruled, max_items],
+    )?;
+    Ok((removed, max_items))
+}
+
+// 从最近使用的记录开始累计大小，超出部分全部删除，返回删除的记录数和保留的记录占用的大小
+fn prune_by_size(
+    conn: &Connection,
+    schema: &str,
+    images: &Path,
+    max_bytes: u64,
+    ruled: &str,
+    counted_images: &mut HashSet<String>,
+) -> rusqlite::Result<(usize, u64)> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, content, content_type FROM {schema}.items WHERE pinned = 0
+         AND id NOT IN (SELECT item_id FROM {schema}.item_tags
+            WHERE tag IN (SELECT value FROM json_each(?1)))
+         ORDER BY last_used DESC, id DESC",
+        schema = schema
+    ))?;
+    let rows = stmt.query_map(params![ruled], |row| {
+        Ok((
+            row.get::<_, i64>("id")?,
+            row.get::<_, String>("content")?,
+            row.get::<_, String>("content_type")?,
+        ))
+    })?;
+
+    let mut total = 0;
+    let mut kept = 0;
+    let mut expired = Vec::new();
+    for row in rows {
+        let (id, content, content_type) = row?;
+        total += if content_type == CONTENT_IMAGE {
+            if counted_images.contains(&content) {
+                0
+            } else {
+                let size = fs::metadata(images.join(&content))
+                    .map(|meta| meta.len())
+                    .unwrap_or_default();
+                counted_images.insert(content);
+                size
+            }
+        } else {
+            content.len() as u64
+        };
+        if total > max_bytes {
+            expired.push(id);
+        } else {
+            kept = total;
+        }
+    }
+
+    let mut delete = conn.prepare(&format!("DELETE FROM {}.items WHERE id = ?1", schema))?;
+    for id in &expired {
+        delete.execute(params![id])?;
+    }
+    Ok((expired.len(), kept))
+}
+
+// 按天数、条数和占用空间清理 schema 中的记录并扣减额度
+fn prune_limits(
+    conn: &Connection,
+    schema: &str,
+    policy: &RetentionPolicy,
+    ruled: &str,
+    images: &Path,
+    budget: &mut Budget,
+) -> rusqlite::Result<usize> {
+    let mut removed = 0;
+    if let Some(days) = policy.max_age_days {
+        removed += prune_by_age(conn, schema, days, ruled)?;
+    }
+    if let Some(max_items) = budget.items {
+        let (pruned, kept) = prune_by_count(conn, schema, max_items, ruled)?;
+        removed += pruned;
+        budget.items = Some(max_items - kept);
+    }
+    if let Some(max_bytes) = budget.bytes {
+        let (pruned, kept) = prune_by_size(
+            conn,
+            schema,
+            images,
+            max_bytes,
+            ruled,
+            &mut budget.counted_images,
+        )?;
+        removed += pruned;
+        budget.bytes = Some(max_bytes - kept);
+    }
+    Ok(removed)
+}
+
+// shards 为 false 时只清理主数据库，为 true 时继续清理分片，清理后没有记录的分片整个删除
+fn prune(
+    conn: &Connection,
+    policy: &RetentionPolicy,
+    images: &Path,
+    shards: bool,
+) -> rusqlite::Result<usize> {
+    let ruled = ruled_tags(policy);
+    let mut removed = prune_expired(conn)?;
+    removed += prune_by_tags(conn, "main", &policy.tag_rules)?;
+
+    let mut budget = Budget {
+        items: policy.max_items,
+        bytes: policy.max_total_mb.map(|mb| mb * MB),
+        counted_images: HashSet::new(),
+    };
+    removed += prune_limits(conn, "main", policy, &ruled, images, &mut budget)?;
+    if !shards {
+        return Ok(removed);
+    }
+    for shard in list_shards(conn) {
+        let (pruned, empty) = with_shard(conn, &shard, || {
+            // 标签规则可能在记录归档之后才添加，分片中的记录同样按标签规则清理
+            let pruned = prune_by_tags(conn, "shard", &policy.tag_rules)?
+                + prune_limits(conn, "shard", policy, &ruled, images, &mut budget)?;
+            let empty: bool =
+                conn.query_row("SELECT NOT EXISTS(SELECT 1 FROM shard.items)", [], |row| {
+                    row.get(0)
+                })?;
+            Ok((pruned, empty))
+        })?;
+        removed += pruned;
+        if empty {
+            remove_shard(&shard);
+        }
+    }
+    Ok(removed)
+}
+
+// 按当前策略清理主数据库并删除不再引用的图片，返回删除的记录数，不处理分片
+// 有记录被删除时发送 history-pruned 事件，前端据此刷新列表
+pub fn run_cleanup<R: Runtime>(app: &AppHandle<R>) -> Result<usize, String> {
+    cleanup(app, false)
+}
+
+// 同 run_cleanup，并清理分片、把本月之前的记录归档
+pub fn run_full_cleanup<R: Runtime>(app: &AppHandle<R>) -> Result<usize, String> {
+    cleanup(app, true)
+}
+
+fn cleanup<R: Runtime>(app: &AppHandle<R>, full: bool) -> Result<usize, String> {
+    let policy = current_settings(app).retention;
+    let images = images_dir(app)?;
+
+    let db = app.state::<Db>();
+    let removed = {
+        let conn = db.0.lock().unwrap();
+        let removed = prune(&conn, &policy, &images, full).map_err(|e| e.to_string())?;
+        if full {
+            archive(&conn, &ruled_tags(&policy))?;
+        }
+        if removed > 0 {
+            let referenced = referenced_images(&conn).map_err(|e| e.to_string())?;
+            remove_unreferenced_images(app, &referenced);
+        }
+        removed
+    };
+
+    if removed > 0 {
+        println!("Retention - Removed {} items", removed);
+        refresh_tray_menu(app);
+        if let Err(e) = app.emit("history-pruned", removed) {
+            println!("Retention - Failed to emit event: {:?}", e);
+        }
+    }
+    Ok(removed)
+}
+
+pub fn next_cleanup() -> Option<i64> {
+    Some(NEXT_CLEANUP.load(Ordering::SeqCst)).filter(|&next| next > 0)
+}
+
+// 新记录设置了到期时间时调用，到期时由定时清理线程删除
+pub fn schedule_expiry(expires_at: i64) {
+    let mut next = NEXT_EXPIRY.lock().unwrap();
+    if next.map_or(true, |next| expires_at < next) {
+        *next = Some(expires_at);
+        EXPIRY_CHANGED.notify_one();
+    }
+}
+
+// 数据库中最早的到期时间，固定的记录不会因到期被删除
+fn earliest_expiry<R: Runtime>(app: &AppHandle<R>) -> Option<i64> {
+    app.state::<Db>()
+        .0
+        .lock()
+        .unwrap()
+        .query_row(
+            "SELECT MIN(expires_at) FROM items WHERE pinned = 0",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or_else(|e| {
+            println!("Retention - Failed to read expiry: {:?}", e);
+            None
+        })
+}
+
+// 启动时执行一次完整清理，之后在后台线程中定时完整清理，并在最早的到期时间醒来删除到期的记录
+pub fn setup_retention<R: Runtime>(app: &AppHandle<R>) {
+    let app_handle = app.clone();
+    thread::spawn(move || {
+        let mut next_full = now_millis();
+        loop {
+            // 清理开始之后登记的到期时间才需要保留
+            NEXT_EXPIRY.lock().unwrap().take();
+            let cleanup = if now_millis() >= next_full {
+                next_full = now_millis() + CLEANUP_INTERVAL.as_millis() as i64;
+                NEXT_CLEANUP.store(next_full, Ordering::SeqCst);
+                run_full_cleanup(&app_handle)
+            } else {
+                run_cleanup(&app_handle)
+            };
+            if let Err(e) = cleanup {
+                println!("Retention - Cleanup failed: {}", e);
+            }
+
+            let earliest = earliest_expiry(&app_handle);
+            let mut next = NEXT_EXPIRY.lock().unwrap();
+            *next = earliest.into_iter().chain(*next).min();
+            loop {
+                let wake_at = next.map_or(next_full, |expiry| expiry.min(next_full));
+                let wait = wake_at - now_millis();
+                if wait <= 0 {
+                    break;
+                }
+                next = EXPIRY_CHANGED
+                    .wait_timeout(next, Duration::from_millis(wait as u64))
+                    .unwrap()
+                    .0;
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_retention_policy<R: Runtime>(app: AppHandle<R>) -> RetentionPolicy {
+    current_settings(&app).retention
+}
+
+// 保存新的保留策略，策略变化时会立即按新策略清理
+#[tauri::command]
+pub fn set_retention_policy<R: Runtime>(
+    app: AppHandle<R>,
+    policy: RetentionPolicy,
+) -> Result<(), String> {
+    update(&app, |settings| settings.retention = policy).map(|_| ())
+}
+
+#[tauri::command]
+pub fn run_cleanup_now<R: Runtime>(app: AppHandle<R>) -> Result<usize, String> {
+    run_full_cleanup(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::db::{connect, get_item, open_in_memory};
+
+    fn insert(conn: &Connection, content: &str, age_minutes: i64, tags: &[&str]) -> i64 {
+        let last_used = now_millis() - age_minutes * MINUTE_MILLIS;
+        conn.execute(
+            "INSERT INTO items (content, content_type, created_at, last_used, hash)
+             VALUES (?1, 'text', ?2, ?2, ?1)",
+            params![content, last_used],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+        for tag in tags {
+            conn.execute(
+                "INSERT INTO item_tags (item_id, tag) VALUES (?1, ?2)",
+                params![id, tag],
+            )
+            .unwrap();
+        }
+        id
+    }
+
+    fn remaining(conn: &Connection) -> Vec<i64> {
+        let mut stmt = conn.prepare("SELECT id FROM items ORDER BY id").unwrap();
+        let ids = stmt.query_map([], |row| row.get(0)).unwrap();
+        ids.map(Result::unwrap).collect()
+    }
+
+    fn tag_rule(tag: &str, max_age_minutes: Option<u64>) -> TagRetention {
+        TagRetention {
+            tag: tag.into(),
+            max_age_minutes,
+        }
+    }
+
+    #[test]
+    fn expired_items_are_removed_unless_pinned() {
+        let conn = open_in_memory();
+        let expired = insert(&conn, "a", 0, &[]);
+        let pinned = insert(&conn, "b", 0, &[]);
+        let future = insert(&conn, "c", 0, &[]);
+        conn.execute(
+            "UPDATE items SET expires_at = ?1 WHERE id IN (?2, ?3)",
+            params![now_millis() - 1, expired, pinned],
+        )
+        .unwrap();
+        conn.execute("UPDATE items SET pinned = 1 WHERE id = ?1", params![pinned])
+            .unwrap();
+        conn.execute(
+            "UPDATE items SET expires_at = ?1 WHERE id = ?2",
+            params![now_millis() + DAY_MILLIS, future],
+        )
+        .unwrap();
+
+        assert_eq!(prune_expired(&conn).unwrap(), 1);
+        assert_eq!(remaining(&conn), vec![pinned, future]);
+    }
+
+    #[test]
+    fn tag_rules_keep_the_longest_retention() {
+        let conn = open_in_memory();
+        let old_otp = insert(&conn, "a", 10, &["otp"]);
+        let recent_otp = insert(&conn, "b", 1, &["otp"]);
+        let kept_receipt = insert(&conn, "c", 10, &["otp", "receipts"]);
+        let untagged = insert(&conn, "d", 10_000, &[]);
+        let rules = [tag_rule("OTP", Some(5)), tag_rule("receipts", None)];
+
+        assert_eq!(prune_by_tags(&conn, "main", &rules).unwrap(), 1);
+        assert!(!remaining(&conn).contains(&old_otp));
+        assert_eq!(remaining(&conn), vec![recent_otp, kept_receipt, untagged]);
+    }
+
+    #[test]
+    fn global_limits_skip_ruled_items() {
+        let conn = open_in_memory();
+        let policy = RetentionPolicy {
+            tag_rules: vec![tag_rule("keep", None)],
+            ..Default::default()
+        };
+        let ruled = ruled_tags(&policy);
+        let old_ruled = insert(&conn, "a", 3 * 24 * 60, &["keep"]);
+        let old = insert(&conn, "b", 3 * 24 * 60, &[]);
+        let older = insert(&conn, "c", 2 * 60, &[]);
+        let newer = insert(&conn, "d", 60, &["other"]);
+        let newest = insert(&conn, "e", 1, &[]);
+
+        assert_eq!(prune_by_age(&conn, "main", 1, &ruled).unwrap(), 1);
+        assert!(!remaining(&conn).contains(&old));
+
+        assert_eq!(prune_by_count(&conn, "main", 3, &ruled).unwrap(), (0, 3));
+        assert_eq!(prune_by_count(&conn, "main", 2, &ruled).unwrap(), (1, 2));
+        assert!(!remaining(&conn).contains(&older));
+        assert_eq!(remaining(&conn), vec![old_ruled, newer, newest]);
+    }
+
+    #[test]
+    fn size_limit_keeps_most_recent_items() {
+        let conn = open_in_memory();
+        let ruled = ruled_tags(&RetentionPolicy::default());
+        let oldest = insert(&conn, "aaaa", 3, &[]);
+        let middle = insert(&conn, "bbbb", 2, &[]);
+        let newest = insert(&conn, "cccc", 1, &[]);
+
+        let mut counted = HashSet::new();
+        assert_eq!(
+            prune_by_size(&conn, "main", Path::new(""), 8, &ruled, &mut counted).unwrap(),
+            (1, 8)
+        );
+        assert!(!remaining(&conn).contains(&oldest));
+        assert_eq!(remaining(&conn), vec![middle, newest]);
+    }
+
+    #[test]
+    fn light_cleanup_checks_the_size_limit() {
+        let conn = open_in_memory();
+        let large = "a".repeat(600 * 1024);
+        let older = insert(&conn, &large, 2, &[]);
+        let newer = insert(&conn, &large.replace('a', "b"), 1, &[]);
+        let policy = RetentionPolicy {
+            max_total_mb: Some(1),
+            ..Default::default()
+        };
+
+        assert_eq!(prune(&conn, &policy, Path::new(""), false).unwrap(), 1);
+        assert!(!remaining(&conn).contains(&older));
+        assert_eq!(remaining(&conn), vec![newer]);
+    }
+
+    #[test]
+    fn shared_images_are_counted_once() {
+        let dir = std::env::temp_dir().join(format!("retention-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("shared.png"), [0u8; 6]).unwrap();
+        let conn = open_in_memory();
+        let ruled = ruled_tags(&RetentionPolicy::default());
+        let text = insert(&conn, "aaaa", 4, &[]);
+        let first = insert(&conn, "shared.png", 2, &[]);
+        let second = insert(&conn, "shared.png", 1, &[]);
+        conn.execute(
+            "UPDATE items SET content_type = ?1 WHERE id IN (?2, ?3)",
+            params![CONTENT_IMAGE, first, second],
+        )
+        .unwrap();
+
+        let mut counted = HashSet::new();
+        let pruned = prune_by_size(&conn, "main", &dir, 8, &ruled, &mut counted).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(pruned, (1, 6));
+        assert_eq!(remaining(&conn), vec![first, second]);
+        assert!(!remaining(&conn).contains(&text));
+    }
+
+    #[test]
+    fn limits_continue_into_shards() {
+        let dir = std::env::temp_dir().join(format!("retention-shards-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let conn = connect(&dir.join("clipper.db"), None).unwrap();
+        let current = insert(&conn, "a", 0, &[]);
+        let last_month = insert(&conn, "b", 40 * 24 * 60, &[]);
+        let older = insert(&conn, "c", 80 * 24 * 60, &[]);
+        archive(&conn, "[]").unwrap();
+        assert_eq!(list_shards(&conn).len(), 2);
+
+        let policy = RetentionPolicy {
+            max_items: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(prune(&conn, &policy, &dir, false).unwrap(), 0);
+        assert_eq!(prune(&conn, &policy, &dir, true).unwrap(), 1);
+        // 最旧的分片清空后整个删除
+        assert_eq!(list_shards(&conn).len(), 1);
+        assert!(get_item(&conn, older).unwrap().is_none());
+        assert_eq!(remaining(&conn), vec![current]);
+        assert!(get_item(&conn, last_month).unwrap().is_some());
+
+        drop(conn);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn tag_rules_apply_to_archived_items() {
+        let dir = std::env::temp_dir().join(format!("retention-tags-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let conn = connect(&dir.join("clipper.db"), None).unwrap();
+        // 归档之后才添加的规则
+        insert(&conn, "a", 40 * 24 * 60, &["otp"]);
+        assert_eq!(archive(&conn, "[]").unwrap(), 1);
+
+        let policy = RetentionPolicy {
+            tag_rules: vec![tag_rule("otp", Some(5))],
+            ..Default::default()
+        };
+        assert_eq!(prune(&conn, &policy, &dir, true).unwrap(), 1);
+        assert!(list_shards(&conn).is_empty());
+
+        drop(conn);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+// core/rich_text.rs
+// 富文本：复制网页、文档时剪贴板中除了纯文本还带有 HTML 和 RTF 格式，一并保存在历史记录中
+// 写回剪贴板时同时写入各个格式，由粘贴的应用选择；也可以只写入指定的格式
+use clipboard_rs::{common::ClipboardContent, Clipboard, ClipboardContext, ContentFormat};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime, State};
+
+use crate::core::db::{rich_text, Db, CONTENT_TEXT};
+use crate::core::sensitive::get_revealed_item;
+
+#[derive(Debug, Clone, Default)]
+pub struct RichText {
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+}
+
+impl RichText {
+    pub fn is_empty(&self) -> bool {
+        self.html.is_none() && self.rtf.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipFormat {
+    Plain,
+    Html,
+    Rtf,
+}
+
+// 读取剪贴板中的 HTML 和 RTF 格式，读取失败或内容为空的格式忽略
+pub fn read_rich_text(ctx: &ClipboardContext) -> RichText {
+    let read = |format: ContentFormat,
+                get: fn(&ClipboardContext) -> clipboard_rs::Result<String>| {
+        if ctx.has(format) {
+            get(ctx).ok().filter(|content| !content.is_empty())
+        } else {
+            None
+        }
+    };
+    RichText {
+        html: read(ContentFormat::Html, |ctx| ctx.get_html()),
+        rtf: read(ContentFormat::Rtf, |ctx| ctx.get_rich_text()),
+    }
+}
+
+// 在元数据中记录保存了哪些富文本格式，前端据此显示复制为 HTML / RTF 的选项
+pub fn mark_rich_text(metadata: Option<Value>, rich: &RichText) -> Value {
+    let mut metadata = metadata.unwrap_or_else(|| json!({}));
+    let formats: Vec<&str> = [("html", &rich.html), ("rtf", &rich.rtf)]
+        .into_iter()
+        .filter(|(_, content)| content.is_some())
+        .map(|(format, _)| format)
+        .collect();
+    metadata["richText"] = json!(formats);
+    metadata
+}
+
+pub fn has_rich_text(metadata: Option<&Value>) -> bool {
+    metadata.is_some_and(|metadata| metadata.get("richText").is_some())
+}
+
+// 同时写入纯文本和保存的富文本格式
+pub fn write_rich_text(ctx: &ClipboardContext, text: &str, rich: RichText) -> Result<(), String> {
+    let mut contents = vec![ClipboardContent::Text(text.to_string())];
+    if let Some(html) = rich.html {
+        contents.push(ClipboardContent::Html(html));
+    }
+    if let Some(rtf) = rich.rtf {
+        contents.push(ClipboardContent::Rtf(rtf));
+    }
+    ctx.set(contents).map_err(|e| e.to_string())
+}
+
+// 以指定格式把文本记录写回剪贴板：纯文本只写入 text/plain，HTML 或 RTF 同时写入纯文本作为后备
+#[tauri::command]
+pub fn copy_item_as<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    id: i64,
+    format: ClipFormat,
+    passcode: Option<String>,
+) -> Result<(), String> {
+    let item = get_revealed_item(&app, id, passcode.as_deref())?;
+    let rich = rich_text(&db.0.lock().unwrap(), id).map_err(|e| e.to_string())?;
+    if item.content_type != CONTENT_TEXT {
+        return Err("该记录不是文本".into());
+    }
+
+    let ctx = ClipboardContext::new().map_err(|e| e.to_string())?;
+    let rich = match format {
+        ClipFormat::Plain => RichText::default(),
+        ClipFormat::Html => RichText {
+            html: Some(rich.html.ok_or("该记录没有 HTML 格式")?),
+            rtf: None,
+        },
+        ClipFormat::Rtf => RichText {
+            html: None,
+            rtf: Some(rich.rtf.ok_or("该记录没有 RTF 格式")?),
+        },
+    };
+    write_rich_text(&ctx, &item.content, rich)
+}
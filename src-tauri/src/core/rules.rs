@@ -0,0 +1,238 @@
+// core/rules.rs
+// 剪贴板规则：新复制的文本匹配规则的正则表达式时执行对应的动作
+// 目前支持“追加到笔记”，把内容按模板追加到 Obsidian/Logseq 库中的 Markdown 文件，路径可按日期生成每日笔记
+use chrono::{DateTime, Local};
+use fs4::fs_std::FileExt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fmt::Write as _,
+    fs::{self, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::core::db::{ClipItem, CONTENT_TEXT};
+use crate::core::sensitive::is_flagged;
+use crate::core::settings::current_settings;
+
+const DEFAULT_TEMPLATE: &str = "- {time} {content}";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RuleAction {
+    // path 和 template 中可以使用 {date}、{date:%Y/%m}、{time}、{content}、{app}、{source} 占位符
+    AppendToNote { path: String, template: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub name: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    // 匹配文本内容的正则表达式，为空时匹配所有文本
+    #[serde(default)]
+    pub pattern: String,
+    pub action: RuleAction,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+// 保存设置前检查所有规则，正则表达式无效或路径为空时返回错误
+pub fn validate_rules(rules: &[Rule]) -> Result<(), String> {
+    for rule in rules {
+        Regex::new(&rule.pattern)
+            .map_err(|e| format!("规则“{}”的正则表达式无效: {}", rule.name, e))?;
+        match &rule.action {
+            RuleAction::AppendToNote { path, .. } if path.trim().is_empty() => {
+                return Err(format!("规则“{}”没有设置笔记路径", rule.name));
+            }
+            RuleAction::AppendToNote { .. } => {}
+        }
+    }
+    Ok(())
+}
+
+// 替换模板中的占位符，未知的占位符和无效的日期格式原样保留
+pub fn expand(template: &str, now: &DateTime<Local>, values: &[(&str, &str)]) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let placeholder = &rest[start..=start + len];
+        let key = &placeholder[1..placeholder.len() - 1];
+        rest = &rest[start + len + 1..];
+
+        let (name, format) = match key.split_once(':') {
+            Some((name, format)) => (name, Some(format)),
+            None => (key, None),
+        };
+        let date_format = match name {
+            "date" => Some(format.unwrap_or("%Y-%m-%d")),
+            "time" => Some(format.unwrap_or("%H:%M")),
+            _ => None,
+        };
+        let mut expanded = String::new();
+        let ok = match date_format {
+            Some(date_format) => write!(expanded, "{}", now.format(date_format)).is_ok(),
+            None => match values.iter().find(|(key, _)| *key == name) {
+                Some((_, value)) if format.is_none() => {
+                    expanded.push_str(value);
+                    true
+                }
+                _ => false,
+            },
+        };
+        output.push_str(if ok { &expanded } else { placeholder });
+    }
+    output.push_str(rest);
+    output
+}
+
+fn note_path<R: Runtime>(app: &AppHandle<R>, path: &str) -> Result<PathBuf, String> {
+    match path.strip_prefix("~/") {
+        Some(rest) => Ok(app.path().home_dir().map_err(|e| e.to_string())?.join(rest)),
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+// 加锁后追加，避免多次复制同时写入同一篇笔记时内容交错
+fn append_to_file(path: &PathBuf, entry: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    // 文件关闭时自动解锁
+    FileExt::lock_exclusive(&file).map_err(|e| e.to_string())?;
+
+    // 原文件末尾没有换行时先补一个，避免与上一行连在一起
+    let mut output = String::new();
+    if file.metadata().map_err(|e| e.to_string())?.len() > 0 {
+        let mut last = [0u8; 1];
+        file.seek(SeekFrom::End(-1)).map_err(|e| e.to_string())?;
+        file.read_exact(&mut last).map_err(|e| e.to_string())?;
+        if last[0] != b'\n' {
+            output.push('\n');
+        }
+    }
+    output.push_str(entry);
+    if !entry.ends_with('\n') {
+        output.push('\n');
+    }
+    file.write_all(output.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn run_action<R: Runtime>(
+    app: &AppHandle<R>,
+    action: &RuleAction,
+    values: &[(&str, &str)],
+) -> Result<(), String> {
+    let now = Local::now();
+    match action {
+        RuleAction::AppendToNote { path, template } => {
+            let template = if template.is_empty() {
+                DEFAULT_TEMPLATE
+            } else {
+                template
+            };
+            let path = note_path(app, &expand(path, &now, &[]))?;
+            append_to_file(&path, &expand(template, &now, values))
+        }
+    }
+}
+
+// 对新复制的文本执行所有匹配的规则，由剪贴板监听在写入历史后调用
+pub fn apply_rules<R: Runtime>(app: &AppHandle<R>, item: &ClipItem) {
+    // 敏感内容和含有密钥的文本不写入笔记
+    if item.content_type != CONTENT_TEXT || is_flagged(item.metadata.as_ref()) {
+        return;
+    }
+    let rules = current_settings(app).rules;
+    if rules.is_empty() {
+        return;
+    }
+
+    let origin = item
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("origin"));
+    let field = |key: &str| {
+        origin
+            .and_then(|origin| origin.get(key))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    };
+    let source = match field("url") {
+        "" => field("path"),
+        url => url,
+    };
+    let values = [
+        ("content", item.content.as_str()),
+        ("app", field("app")),
+        ("source", source),
+    ];
+
+    for rule in rules.iter().filter(|rule| rule.enabled) {
+        let matched = Regex::new(&rule.pattern).is_ok_and(|regex| regex.is_match(&item.content));
+        if !matched {
+            continue;
+        }
+        if let Err(e) = run_action(app, &rule.action, &values) {
+            println!("Rules - Rule {} failed: {}", rule.name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 6, 7, 8, 9).unwrap()
+    }
+
+    #[test]
+    fn expands_dates_and_values() {
+        let values = [("content", "hello"), ("app", "Code")];
+        assert_eq!(
+            expand("- {date} {time} {content} ({app})", &now(), &values),
+            "- 2024-05-06 07:08 hello (Code)"
+        );
+        assert_eq!(
+            expand("journal/{date:%Y/%m}/{date:%d}.md", &now(), &values),
+            "journal/2024/05/06.md"
+        );
+    }
+
+    #[test]
+    fn unknown_placeholders_are_kept() {
+        let values = [("content", "hello")];
+        assert_eq!(
+            expand("{foo} {content:x}", &now(), &values),
+            "{foo} {content:x}"
+        );
+        assert_eq!(expand("{date:%Q}", &now(), &values), "{date:%Q}");
+        assert_eq!(expand("a {content", &now(), &values), "a {content");
+    }
+
+    #[test]
+    fn values_are_not_expanded_again() {
+        let values = [("content", "{date}")];
+        assert_eq!(expand("{content}", &now(), &values), "{date}");
+    }
+}
@@ -0,0 +1,245 @@
+// core/schedule.rs
+// 定时任务：按墙上时间执行的任务（每天的数据库维护）在这里按时区计算下次执行时间
+// 时区可以在设置中指定 IANA 名称（如 Asia/Shanghai），不指定时使用系统时区
+// 夏令时切换时，当天不存在的时刻（如 02:30 被跳过）顺延到切换后的第一个有效时刻；
+// 出现两次的时刻只在第一次执行，下次执行时间总是晚于本次，因此不会重复执行也不会漏掉
+// 按固定间隔执行的任务（历史清理、空闲锁定检查）使用单调时钟休眠，不受时区和夏令时影响
+use chrono::{
+    DateTime, Duration as ChronoDuration, Local, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::{thread, time::Duration};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::core::db::{maintain, Db};
+use crate::core::retention::{next_cleanup, run_full_cleanup};
+use crate::core::settings::current_settings;
+
+// 检查是否到达执行时间的间隔，设置变化后最迟在这段时间后生效
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+// 跳过的时刻每次顺延的分钟数，夏令时切换的间隔都是它的整数倍
+const GAP_STEP_MINUTES: i64 = 15;
+const DEFAULT_RUN_COUNT: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScheduleSettings {
+    // IANA 时区名称，None 表示使用系统时区
+    pub timezone: Option<String>,
+    // 每天执行数据库维护的时间（HH:MM），None 表示不执行
+    pub maintenance_at: Option<String>,
+}
+
+impl Default for ScheduleSettings {
+    fn default() -> Self {
+        Self {
+            timezone: None,
+            maintenance_at: Some("03:30".into()),
+        }
+    }
+}
+
+// get_next_run_times 返回的一个执行时间
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledRun {
+    pub job: String,
+    // Unix 时间戳（毫秒）
+    pub at: i64,
+    // 按设置的时区显示的时间（RFC 3339）
+    pub local: String,
+}
+
+fn parse_timezone(name: Option<&str>) -> Result<Option<Tz>, String> {
+    name.map(|name| {
+        name.parse::<Tz>()
+            .map_err(|_| format!("无效的时区: {}", name))
+    })
+    .transpose()
+}
+
+fn parse_time(at: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(at.trim(), "%H:%M").map_err(|_| format!("无效的时间: {}", at))
+}
+
+pub fn validate_schedule(settings: &ScheduleSettings) -> Result<(), String> {
+    parse_timezone(settings.timezone.as_deref())?;
+    if let Some(at) = &settings.maintenance_at {
+        parse_time(at)?;
+    }
+    Ok(())
+}
+
+// 本地日期 date 的 at 时刻对应的时间，不存在时顺延，出现两次时取第一次
+fn resolve<T: TimeZone>(tz: &T, date: NaiveDate, at: NaiveTime) -> DateTime<Utc> {
+    let mut local = date.and_time(at);
+    loop {
+        match tz.from_local_datetime(&local) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => {
+                return time.with_timezone(&Utc)
+            }
+            LocalResult::None => local += ChronoDuration::minutes(GAP_STEP_MINUTES),
+        }
+    }
+}
+
+// 晚于 after 的第一个每天 at 时刻
+pub fn next_daily_run<T: TimeZone>(tz: &T, at: NaiveTime, after: DateTime<Utc>) -> DateTime<Utc> {
+    let mut date = after.with_timezone(tz).date_naive();
+    loop {
+        let run = resolve(tz, date, at);
+        if run > after {
+            return run;
+        }
+        date = date.succ_opt().unwrap_or(date);
+    }
+}
+
+// 按设置的时区计算，时区无效时使用系统时区
+fn next_run(settings: &ScheduleSettings, at: NaiveTime, after: DateTime<Utc>) -> DateTime<Utc> {
+    match parse_timezone(settings.timezone.as_deref()) {
+        Ok(Some(tz)) => next_daily_run(&tz, at, after),
+        _ => next_daily_run(&Local, at, after),
+    }
+}
+
+fn format_local(settings: &ScheduleSettings, time: DateTime<Utc>) -> String {
+    match parse_timezone(settings.timezone.as_deref()) {
+        Ok(Some(tz)) => time.with_timezone(&tz).to_rfc3339(),
+        _ => time.with_timezone(&Local).to_rfc3339(),
+    }
+}
+
+fn next_maintenance(settings: &ScheduleSettings, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let at = parse_time(settings.maintenance_at.as_deref()?).ok()?;
+    Some(next_run(settings, at, after))
+}
+
+// 清理历史，然后合并 WAL 日志并更新查询统计
+fn run_maintenance<R: Runtime>(app: &AppHandle<R>) {
+    if let Err(e) = run_full_cleanup(app) {
+        println!("Schedule - Cleanup failed: {}", e);
+    }
+    match maintain(&app.state::<Db>().0.lock().unwrap()) {
+        Ok(()) => println!("Schedule - Database maintenance finished"),
+        Err(e) => println!("Schedule - Database maintenance failed: {:?}", e),
+    }
+}
+
+// 在后台线程中按设置的时间执行每天的维护；设置变化后从当前时间重新计算下次执行时间
+pub fn setup_schedule<R: Runtime>(app: &AppHandle<R>) {
+    let app_handle = app.clone();
+    thread::spawn(move || {
+        let mut planned: Option<(ScheduleSettings, Option<DateTime<Utc>>)> = None;
+        loop {
+            let settings = current_settings(&app_handle).schedule;
+            let now = Utc::now();
+            let next = match &planned {
+                Some((planned_settings, next)) if *planned_settings == settings => *next,
+                _ => next_maintenance(&settings, now),
+            };
+            let next = match next {
+                // 电脑休眠时错过的执行在唤醒后只补一次，下次从现在往后计算
+                Some(run) if run <= now => {
+                    run_maintenance(&app_handle);
+                    next_maintenance(&settings, Utc::now())
+                }
+                next => next,
+            };
+            planned = Some((settings, next));
+            thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
+
+// 调试用：列出各定时任务接下来的执行时间，每天的任务返回 count 次（默认 3 次）
+#[tauri::command]
+pub fn get_next_run_times<R: Runtime>(
+    app: AppHandle<R>,
+    count: Option<usize>,
+) -> Vec<ScheduledRun> {
+    let settings = current_settings(&app).schedule;
+    let run = |job: &str, time: DateTime<Utc>| ScheduledRun {
+        job: job.into(),
+        at: time.timestamp_millis(),
+        local: format_local(&settings, time),
+    };
+
+    let mut runs = Vec::new();
+    if let Some(time) = next_cleanup().and_then(DateTime::from_timestamp_millis) {
+        runs.push(run("cleanup", time));
+    }
+    let mut after = Utc::now();
+    for _ in 0..count.unwrap_or(DEFAULT_RUN_COUNT) {
+        let Some(time) = next_maintenance(&settings, after) else {
+            break;
+        };
+        runs.push(run("maintenance", time));
+        after = time;
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::New_York;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        text.parse().unwrap()
+    }
+
+    fn time(at: &str) -> NaiveTime {
+        parse_time(at).unwrap()
+    }
+
+    #[test]
+    fn skipped_time_moves_to_first_valid_time() {
+        // 2024-03-10 02:00 纽约时间拨快到 03:00
+        let date = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        assert_eq!(
+            resolve(&New_York, date, time("02:30")),
+            utc("2024-03-10T07:00:00Z")
+        );
+    }
+
+    #[test]
+    fn repeated_time_uses_first_occurrence() {
+        // 2024-11-03 02:00 纽约时间拨回到 01:00，01:30 出现两次
+        let date = NaiveDate::from_ymd_opt(2024, 11, 3).unwrap();
+        assert_eq!(
+            resolve(&New_York, date, time("01:30")),
+            utc("2024-11-03T05:30:00Z")
+        );
+    }
+
+    #[test]
+    fn next_daily_run_is_after_given_time() {
+        assert_eq!(
+            next_daily_run(&New_York, time("03:30"), utc("2024-01-01T00:00:00Z")),
+            utc("2024-01-01T08:30:00Z")
+        );
+        assert_eq!(
+            next_daily_run(&New_York, time("03:30"), utc("2024-01-01T08:30:00Z")),
+            utc("2024-01-02T08:30:00Z")
+        );
+    }
+
+    #[test]
+    fn next_daily_run_does_not_repeat_on_fall_back() {
+        // 第一次 01:30 执行后，下次是第二天，而不是一小时后的第二个 01:30
+        assert_eq!(
+            next_daily_run(&New_York, time("01:30"), utc("2024-11-03T05:30:00Z")),
+            utc("2024-11-04T06:30:00Z")
+        );
+    }
+
+    #[test]
+    fn next_daily_run_does_not_skip_on_spring_forward() {
+        assert_eq!(
+            next_daily_run(&New_York, time("02:30"), utc("2024-03-10T05:00:00Z")),
+            utc("2024-03-10T07:00:00Z")
+        );
+    }
+}
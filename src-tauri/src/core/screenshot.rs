@@ -0,0 +1,143 @@
+// core/screenshot.rs
+// PrintScreen 截图：可选注册 PrintScreen 键，按下后调用系统截图工具把截图放入剪贴板，
+// 剪贴板监听随后保存这张图片，并在元数据中标记为截图（screenshot 字段，包含空的批注列表供前端编辑）
+// 不注册时按键完全交给系统处理；与系统截图工具共存时，系统截图工具复制的图片同样标记为截图
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+use std::{
+    process::Command,
+    sync::atomic::{AtomicI64, Ordering},
+    thread,
+    time::Duration,
+};
+
+use crate::core::db::now_millis;
+use crate::core::source_app::SourceApp;
+
+pub const PRINT_SCREEN_SHORTCUT: &str = "PrintScreen";
+// 按下 PrintScreen 后在这段时间内出现的图片视为截图，框选区域需要用户操作，留出足够的时间
+const PENDING_WINDOW: Duration = Duration::from_secs(2 * 60);
+
+// 等待中的截图的截止时间（毫秒），0 表示没有等待中的截图
+static PENDING_UNTIL: AtomicI64 = AtomicI64::new(0);
+
+// 系统截图工具的应用名或进程名，复制截图时这些工具通常仍在前台
+const SCREENSHOT_TOOLS: &[&str] = &[
+    "ScreenClippingHost",
+    "SnippingTool",
+    "ScreenSketch",
+    "screencaptureui",
+    "Screenshot",
+    "gnome-screenshot",
+    "spectacle",
+    "flameshot",
+    "ksnip",
+];
+
+// Windows 上用 PowerShell 截取所有显示器并写入剪贴板，剪贴板操作需要 STA 线程
+#[cfg(windows)]
+const FULLSCREEN_SCRIPT: &str = "Add-Type -AssemblyName System.Windows.Forms, System.Drawing; \
+    $b = [System.Windows.Forms.SystemInformation]::VirtualScreen; \
+    $bmp = New-Object System.Drawing.Bitmap $b.Width, $b.Height; \
+    [System.Drawing.Graphics]::FromImage($bmp).CopyFromScreen($b.Location, [System.Drawing.Point]::Empty, $b.Size); \
+    [System.Windows.Forms.Clipboard]::SetImage($bmp)";
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrintScreenMode {
+    // 不注册 PrintScreen，交给系统截图工具
+    #[default]
+    Off,
+    // 不注册 PrintScreen，系统截图工具复制到剪贴板的图片标记为截图
+    Coexist,
+    // 框选区域截图
+    Region,
+    // 截取整个屏幕
+    Fullscreen,
+}
+
+impl PrintScreenMode {
+    // 是否注册 PrintScreen 键，由应用调用截图工具
+    pub fn intercepts(self) -> bool {
+        matches!(self, Self::Region | Self::Fullscreen)
+    }
+}
+
+// 各平台截图到剪贴板的命令
+fn capture_command(mode: PrintScreenMode) -> Option<Command> {
+    let region = mode == PrintScreenMode::Region;
+    if cfg!(target_os = "windows") {
+        #[cfg(windows)]
+        if !region {
+            let mut command = Command::new("powershell.exe");
+            command
+                .args(["-NoProfile", "-STA", "-Command", FULLSCREEN_SCRIPT])
+                .creation_flags(CREATE_NO_WINDOW);
+            return Some(command);
+        }
+        let mut command = Command::new("explorer.exe");
+        command.arg("ms-screenclip:");
+        Some(command)
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("screencapture");
+        command.arg(if region { "-ic" } else { "-c" });
+        Some(command)
+    } else if cfg!(target_os = "linux") {
+        let mut command = Command::new("gnome-screenshot");
+        command.arg("-c");
+        if region {
+            command.arg("-a");
+        }
+        Some(command)
+    } else {
+        None
+    }
+}
+
+// PrintScreen 按下时调用系统截图工具，并等待截图出现在剪贴板中
+pub fn take_screenshot(mode: PrintScreenMode) {
+    let Some(mut command) = capture_command(mode) else {
+        return;
+    };
+    match command.spawn() {
+        Ok(mut child) => {
+            PENDING_UNTIL.store(
+                now_millis() + PENDING_WINDOW.as_millis() as i64,
+                Ordering::SeqCst,
+            );
+            // 截图工具退出后回收进程，避免留下僵尸进程
+            thread::spawn(move || {
+                if let Err(e) = child.wait() {
+                    println!("Screenshot - Failed to wait for screenshot tool: {:?}", e);
+                }
+            });
+        }
+        Err(e) => println!("Screenshot - Failed to start screenshot tool: {:?}", e),
+    }
+}
+
+fn from_screenshot_tool(source: Option<&SourceApp>) -> bool {
+    source.is_some_and(|source| SCREENSHOT_TOOLS.iter().any(|tool| source.matches(tool)))
+}
+
+// 剪贴板监听保存图片时调用：有等待中的截图，或共存模式下图片来自系统截图工具时返回截图元数据
+pub fn take_screenshot_metadata(
+    mode: PrintScreenMode,
+    source: Option<&SourceApp>,
+) -> Option<Value> {
+    let until = PENDING_UNTIL.swap(0, Ordering::SeqCst);
+    let now = now_millis();
+    let coexisting = mode == PrintScreenMode::Coexist && from_screenshot_tool(source);
+    (until >= now || coexisting).then(|| {
+        json!({
+            "screenshot": {
+                "capturedAt": now,
+                "annotations": [],
+            }
+        })
+    })
+}
@@ -0,0 +1,218 @@
+// core/search.rs
+// 历史全文搜索：items_fts 是 items 的 FTS5 外部内容索引，由触发器与 items 同步（见 db.rs 迁移 4）
+// 只索引文本和文件列表，图片记录的 content 只是文件名
+// 归档的分片各有自己的索引，分别搜索后按相关度合并
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::{AppHandle, Runtime, State};
+
+use crate::core::db::{ClipItem, Db};
+use crate::core::lock::AppLock;
+use crate::core::settings::current_settings;
+use crate::core::shards::query_shards;
+
+// snippet() 用控制字符标记命中的词，返回前再转换为偏移量
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+// 带 "shard." 前缀时搜索附加的分片
+macro_rules! search_sql {
+    ($schema:literal) => {
+        concat!(
+            "SELECT items.id, items.content, items.content_type, items.created_at,
+            items.last_used, items.hash, items.metadata, items.pinned, items.expires_at,
+            (SELECT json_group_array(tag) FROM ",
+            $schema,
+            "item_tags WHERE item_id = items.id) AS tags,
+            snippet(items_fts, 0, char(2), char(3), '…', 32) AS snippet, rank
+     FROM ",
+            $schema,
+            "items_fts AS items_fts JOIN ",
+            $schema,
+            "items AS items ON items.id = items_fts.rowid
+     WHERE items_fts MATCH ?1
+       AND (?3 = 0 OR json_extract(items.metadata, '$.secret') IS NULL)
+     ORDER BY rank, items.last_used DESC
+     LIMIT ?2"
+        )
+    };
+}
+
+pub const SEARCH_SQL: &str = search_sql!("");
+const SHARD_SEARCH_SQL: &str = search_sql!("shard.");
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub item: ClipItem,
+    // 命中位置附近的片段，长内容只返回片段
+    pub snippet: String,
+    // 命中词在 snippet 中的 [开始, 结束) 偏移，按 UTF-16 码元计算，与前端字符串下标一致
+    pub highlights: Vec<[usize; 2]>,
+}
+
+// 把用户输入转换为 FTS5 查询：每个词加引号避免被当作查询语法，并按前缀匹配
+fn to_fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 去掉 snippet 中的标记字符，同时记录命中区间
+fn parse_snippet(marked: &str) -> (String, Vec<[usize; 2]>) {
+    let mut snippet = String::with_capacity(marked.len());
+    let mut highlights = Vec::new();
+    let mut offset = 0;
+    let mut start = None;
+
+    for ch in marked.chars() {
+        match ch {
+            MATCH_START => start = Some(offset),
+            MATCH_END => {
+                if let Some(start) = start.take() {
+                    highlights.push([start, offset]);
+                }
+            }
+            _ => {
+                snippet.push(ch);
+                offset += ch.len_utf16();
+            }
+        }
+    }
+    (snippet, highlights)
+}
+
+// 按相关度搜索历史，多个词之间为“与”关系，每个词按前缀匹配
+// 隐藏密钥时含有密钥的记录不出现在结果中，否则逐个前缀查询是否命中就能还原出密钥
+#[tauri::command]
+pub fn search_history<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    query: String,
+    limit: u32,
+) -> Result<Vec<SearchResult>, String> {
+    lock.ensure_unlocked()?;
+    let fts_query = to_fts_query(&query);
+    if fts_query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let conceal_secrets = current_settings(&app).capture.sensitive.conceal_secrets;
+    let conn = db.0.lock().unwrap();
+    search(&conn, &fts_query, limit, conceal_secrets).map_err(|e| e.to_string())
+}
+
+fn search(
+    conn: &Connection,
+    fts_query: &str,
+    limit: u32,
+    conceal_secrets: bool,
+) -> rusqlite::Result<Vec<SearchResult>> {
+    let params = params![fts_query, limit, conceal_secrets];
+    let to_result = |row: &rusqlite::Row<'_>| {
+        let marked: String = row.get("snippet")?;
+        let (snippet, highlights) = parse_snippet(&marked);
+        Ok((
+            row.get::<_, f64>("rank")?,
+            SearchResult {
+                item: ClipItem::from_row(row)?,
+                snippet,
+                highlights,
+            },
+        ))
+    };
+    let mut results: Vec<(f64, SearchResult)> = conn
+        .prepare_cached(SEARCH_SQL)?
+        .query_map(params, to_result)?
+        .collect::<rusqlite::Result<_>>()?;
+    results.extend(query_shards(conn, |_| {
+        conn.prepare(SHARD_SEARCH_SQL)?
+            .query_map(params, to_result)?
+            .collect()
+    })?);
+
+    // 各分片的相关度按各自的索引统计计算，合并时只是近似排序
+    results.sort_by(|(a_rank, a), (b_rank, b)| {
+        a_rank
+            .total_cmp(b_rank)
+            .then(b.item.last_used.cmp(&a.item.last_used))
+    });
+    results.truncate(limit as usize);
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::db::{connect, insert_item, now_millis, CONTENT_TEXT};
+    use crate::core::shards::archive;
+
+    #[test]
+    fn terms_are_quoted_prefix_queries() {
+        assert_eq!(to_fts_query("  foo   bar "), "\"foo\"* \"bar\"*");
+        assert_eq!(to_fts_query("say\"hi"), "\"say\"\"hi\"*");
+        assert_eq!(to_fts_query("a OR b"), "\"a\"* \"OR\"* \"b\"*");
+        assert_eq!(to_fts_query("   "), "");
+    }
+
+    #[test]
+    fn snippet_markers_become_utf16_ranges() {
+        let marked = format!(
+            "a{}bc{}d {}e{}",
+            MATCH_START, MATCH_END, MATCH_START, MATCH_END
+        );
+        assert_eq!(
+            parse_snippet(&marked),
+            ("abcd e".to_string(), vec![[1, 3], [5, 6]])
+        );
+
+        let marked = format!("😀{}中文{}", MATCH_START, MATCH_END);
+        assert_eq!(parse_snippet(&marked), ("😀中文".to_string(), vec![[2, 4]]));
+    }
+
+    #[test]
+    fn unmatched_markers_are_ignored() {
+        let marked = format!("{}ab{}c{}", MATCH_END, MATCH_START, MATCH_START);
+        assert_eq!(parse_snippet(&marked), ("abc".to_string(), vec![]));
+    }
+
+    #[test]
+    fn archived_shards_are_searched() {
+        let dir = std::env::temp_dir().join(format!("search-shards-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = connect(&dir.join("clipper.db"), None).unwrap();
+        for (content, last_used) in [("alpha current", now_millis()), ("alpha archived", 0)] {
+            let id = insert_item(&conn, content, CONTENT_TEXT, content, None, None)
+                .unwrap()
+                .id;
+            conn.execute(
+                "UPDATE items SET last_used = ?1 WHERE id = ?2",
+                params![last_used, id],
+            )
+            .unwrap();
+        }
+        assert_eq!(archive(&conn, "[]").unwrap(), 1);
+
+        let found = search(&conn, &to_fts_query("alpha"), 10, false).unwrap();
+        let mut contents: Vec<&str> = found.iter().map(|r| r.item.content.as_str()).collect();
+        contents.sort();
+        assert_eq!(contents, vec!["alpha archived", "alpha current"]);
+        assert_eq!(
+            search(&conn, &to_fts_query("archived"), 10, false).unwrap()[0].highlights,
+            vec![[6, 14]]
+        );
+        assert_eq!(
+            search(&conn, &to_fts_query("alpha"), 1, false)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        drop(conn);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,353 @@
+// core/settings.rs
+// 应用设置：所有设置集中保存在配置目录的 settings.json，启动时加载为托管状态
+// 各模块通过 current_settings 读取，修改统一经过 update_settings，由这里负责校验、保存并通知各模块生效
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, RwLock},
+};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::core::appearance::{apply_appearance, Appearance};
+use crate::core::clipboard_watcher::{apply_capture_settings, CaptureSettings};
+use crate::core::hotkeys::{rebind_hotkeys, Hotkeys};
+use crate::core::http::{validate_proxy, ProxySettings};
+use crate::core::issue::DEFAULT_ISSUE_TEMPLATE;
+use crate::core::lock::{AppLock, LockSettings};
+use crate::core::retention::{run_full_cleanup, validate_retention, RetentionPolicy};
+use crate::core::rules::{validate_rules, Rule};
+use crate::core::schedule::{validate_schedule, ScheduleSettings};
+use crate::core::transform::{validate_transform_rules, TransformRule};
+use crate::core::tray::refresh_tray_menu;
+use crate::core::watch_folder::{apply_watch_folders, validate_watch_folders, WatchFolder};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    // 剪贴板轮询间隔（毫秒）
+    pub poll_interval_ms: u64,
+    // 界面语言，由前端使用
+    pub language: String,
+    // 托盘菜单中显示的最近记录条数，0 表示不显示
+    pub tray_recent_items: u32,
+    pub appearance: Appearance,
+    pub hotkeys: Hotkeys,
+    pub capture: CaptureSettings,
+    pub retention: RetentionPolicy,
+    pub proxy: ProxySettings,
+    pub rules: Vec<Rule>,
+    // 复制文本时按顺序执行的自动转换规则
+    pub transforms: Vec<TransformRule>,
+    // 整理 Issue 正文使用的模板
+    pub issue_template: String,
+    pub lock: LockSettings,
+    pub schedule: ScheduleSettings,
+    pub watch_folders: Vec<WatchFolder>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 500,
+            language: "zh-CN".into(),
+            tray_recent_items: 10,
+            appearance: Appearance::default(),
+            hotkeys: Hotkeys::default(),
+            capture: CaptureSettings::default(),
+            retention: RetentionPolicy::default(),
+            proxy: ProxySettings::default(),
+            rules: Vec::new(),
+            transforms: Vec::new(),
+            issue_template: DEFAULT_ISSUE_TEMPLATE.into(),
+            lock: LockSettings::default(),
+            schedule: ScheduleSettings::default(),
+            watch_folders: Vec::new(),
+        }
+    }
+}
+
+pub struct SettingsState {
+    settings: RwLock<Settings>,
+    // 修改设置时从读取到保存、通知各模块全程持有，保证并发的修改逐个进行，不会互相覆盖
+    // 读取只用 settings 的读锁，各模块在生效过程中仍可以调用 current_settings
+    writer: Mutex<()>,
+}
+
+fn config_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path().app_config_dir().map_err(|e| e.to_string())
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, settings: &Settings) -> Result<(), String> {
+    let dir = config_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let text = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(dir.join(SETTINGS_FILE), text).map_err(|e| e.to_string())
+}
+
+// 读取旧版本按模块分开保存的设置文件，文件不存在或格式无效时返回 None
+fn read_legacy<T: DeserializeOwned>(dir: &Path, file: &str) -> Option<T> {
+    let text = fs::read_to_string(dir.join(file)).ok()?;
+    match serde_json::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            println!("Settings - Invalid legacy {}, keeping it: {:?}", file, e);
+            None
+        }
+    }
+}
+
+// 没有 settings.json 时从旧的设置文件迁移
+// 新设置保存成功后才删除迁移过的旧文件；格式无效的旧文件保留，方便手动恢复
+fn migrate_legacy<R: Runtime>(app: &AppHandle<R>, dir: &Path) -> Settings {
+    let mut settings = Settings::default();
+    let mut migrated = Vec::new();
+    if let Some(appearance) = read_legacy(dir, "appearance.json") {
+        settings.appearance = appearance;
+        migrated.push("appearance.json");
+    }
+    if let Some(hotkeys) = read_legacy(dir, "hotkeys.json") {
+        settings.hotkeys = hotkeys;
+        migrated.push("hotkeys.json");
+    }
+    if let Some(capture) = read_legacy(dir, "capture.json") {
+        settings.capture = capture;
+        migrated.push("capture.json");
+    }
+    if let Some(retention) = read_legacy(dir, "retention.json") {
+        settings.retention = retention;
+        migrated.push("retention.json");
+    }
+    if let Some(proxy) = read_legacy(dir, "proxy.json") {
+        settings.proxy = proxy;
+        migrated.push("proxy.json");
+    }
+    if let Err(e) = save(app, &settings) {
+        println!("Settings - Failed to save migrated settings: {}", e);
+        return settings;
+    }
+    for file in migrated {
+        if let Err(e) = fs::remove_file(dir.join(file)) {
+            println!("Settings - Failed to remove legacy {}: {:?}", file, e);
+        }
+    }
+    settings
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>) -> Settings {
+    let Ok(dir) = config_dir(app) else {
+        return Settings::default();
+    };
+    match fs::read_to_string(dir.join(SETTINGS_FILE)) {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+            println!("Settings - Invalid settings file, using defaults: {:?}", e);
+            Settings::default()
+        }),
+        Err(_) => migrate_legacy(app, &dir),
+    }
+}
+
+// 启动时加载设置，必须在其他模块初始化之前调用
+pub fn setup_settings<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(SettingsState {
+        settings: RwLock::new(load(app)),
+        writer: Mutex::new(()),
+    });
+}
+
+pub fn current_settings<R: Runtime>(app: &AppHandle<R>) -> Settings {
+    app.state::<SettingsState>()
+        .settings
+        .read()
+        .unwrap()
+        .clone()
+}
+
+// JSON Merge Patch（RFC 7386）：对象逐字段合并，null 表示恢复默认值
+fn merge_patch(target: &mut Value, patch: Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(&key);
+                } else {
+                    merge_patch(target.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+// 是否关闭了密钥的保护：列表中隐藏密钥，或查看密钥需要密码
+fn weakens_secret_protection(old: &Settings, new: &Settings) -> bool {
+    (old.capture.sensitive.conceal_secrets && !new.capture.sensitive.conceal_secrets)
+        || (old.lock.reveal_requires_passcode && !new.lock.reveal_requires_passcode)
+}
+
+// 应用新设置：先做可能失败的校验和快捷键注册，成功后保存并通知各模块；锁定期间不能修改设置
+// 调用方需要持有 SettingsState::writer
+// 设置了锁定密码时，关闭密钥保护需要提供锁定密码，否则前端可以直接绕过 reveal_entry 的密码验证
+fn commit<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: Settings,
+    passcode: Option<&str>,
+) -> Result<Settings, String> {
+    let old = current_settings(app);
+    let lock = app.state::<AppLock>();
+    lock.ensure_unlocked()?;
+    if weakens_secret_protection(&old, &settings)
+        && lock.is_enabled()
+        && !lock.verify_passcode(passcode.unwrap_or_default())?
+    {
+        return Err("密码错误".into());
+    }
+    if old.proxy != settings.proxy {
+        validate_proxy(&settings.proxy)?;
+    }
+    if old.retention != settings.retention {
+        validate_retention(&settings.retention)?;
+    }
+    if old.schedule != settings.schedule {
+        validate_schedule(&settings.schedule)?;
+    }
+    if old.watch_folders != settings.watch_folders {
+        validate_watch_folders(&settings.watch_folders)?;
+    }
+    if old.rules != settings.rules {
+        validate_rules(&settings.rules)?;
+    }
+    if old.transforms != settings.transforms {
+        validate_transform_rules(&settings.transforms)?;
+    }
+    if old.hotkeys != settings.hotkeys {
+        rebind_hotkeys(app, &old.hotkeys, &settings.hotkeys)?;
+    }
+
+    if let Err(e) = save(app, &settings) {
+        // 保存失败时恢复原来的快捷键，保持与设置文件一致
+        if old.hotkeys != settings.hotkeys {
+            let _ = rebind_hotkeys(app, &settings.hotkeys, &old.hotkeys);
+        }
+        return Err(e);
+    }
+    *app.state::<SettingsState>().settings.write().unwrap() = settings.clone();
+
+    if old.appearance != settings.appearance {
+        apply_appearance(app, &settings.appearance);
+    }
+    if old.capture != settings.capture {
+        apply_capture_settings(app, &settings.capture);
+    }
+    if old.retention != settings.retention {
+        if let Err(e) = run_full_cleanup(app) {
+            println!("Settings - Cleanup failed: {}", e);
+        }
+    }
+    if old.watch_folders != settings.watch_folders {
+        apply_watch_folders(app, &settings.watch_folders);
+    }
+    if old.tray_recent_items != settings.tray_recent_items {
+        refresh_tray_menu(app);
+    }
+    if let Err(e) = app.emit("settings-changed", &settings) {
+        println!("Settings - Failed to emit event: {:?}", e);
+    }
+    Ok(settings)
+}
+
+// 供各模块的专用命令修改自己那部分设置
+// 持有 writer 期间读取、修改并提交，change 和各模块生效时不能再调用 update
+pub fn update<R: Runtime>(
+    app: &AppHandle<R>,
+    change: impl FnOnce(&mut Settings),
+) -> Result<Settings, String> {
+    let state = app.state::<SettingsState>();
+    let _writer = state.writer.lock().unwrap();
+    let mut settings = current_settings(app);
+    change(&mut settings);
+    commit(app, settings, None)
+}
+
+#[tauri::command]
+pub fn get_settings(state: State<'_, SettingsState>) -> Settings {
+    state.settings.read().unwrap().clone()
+}
+
+// 按 JSON Merge Patch 修改设置，只需传入变化的字段，返回修改后的完整设置
+// 关闭隐藏密钥或查看密钥需要密码时，需要同时传入锁定密码
+#[tauri::command]
+pub async fn update_settings<R: Runtime>(
+    app: AppHandle<R>,
+    patch: Value,
+    passcode: Option<String>,
+) -> Result<Settings, String> {
+    let state = app.state::<SettingsState>();
+    let _writer = state.writer.lock().unwrap();
+    let mut merged = serde_json::to_value(current_settings(&app)).map_err(|e| e.to_string())?;
+    merge_patch(&mut merged, patch);
+    let settings = serde_json::from_value(merged).map_err(|e| format!("设置格式无效: {}", e))?;
+    commit(&app, settings, passcode.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_patch_merges_objects_recursively() {
+        let mut target = json!({"a": 1, "b": {"c": 2, "d": 3}});
+        merge_patch(&mut target, json!({"b": {"c": 4, "e": 5}, "f": 6}));
+        assert_eq!(
+            target,
+            json!({"a": 1, "b": {"c": 4, "d": 3, "e": 5}, "f": 6})
+        );
+    }
+
+    #[test]
+    fn merge_patch_null_removes_field() {
+        let mut target = json!({"a": 1, "b": {"c": 2, "d": 3}});
+        merge_patch(&mut target, json!({"a": null, "b": {"c": null}}));
+        assert_eq!(target, json!({"b": {"d": 3}}));
+    }
+
+    #[test]
+    fn merge_patch_replaces_non_objects() {
+        let mut target = json!({"list": [1, 2], "value": {"x": 1}});
+        merge_patch(&mut target, json!({"list": [3], "value": "text"}));
+        assert_eq!(target, json!({"list": [3], "value": "text"}));
+
+        let mut target = json!([1]);
+        merge_patch(&mut target, json!({"a": 1}));
+        assert_eq!(target, json!({"a": 1}));
+    }
+
+    #[test]
+    fn removed_fields_fall_back_to_defaults() {
+        let mut merged = serde_json::to_value(Settings::default()).unwrap();
+        merge_patch(&mut merged, json!({"pollIntervalMs": 1000}));
+        merge_patch(&mut merged, json!({"pollIntervalMs": null}));
+        let settings: Settings = serde_json::from_value(merged).unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn invalid_legacy_files_are_kept() {
+        let dir = std::env::temp_dir().join(format!("settings-legacy-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("proxy.json"), "{not json").unwrap();
+        fs::write(dir.join("appearance.json"), r#"{"compact": true}"#).unwrap();
+
+        assert!(read_legacy::<ProxySettings>(&dir, "proxy.json").is_none());
+        assert!(dir.join("proxy.json").exists());
+        let appearance: Appearance = read_legacy(&dir, "appearance.json").unwrap();
+        assert!(appearance.compact);
+        assert!(read_legacy::<Appearance>(&dir, "missing.json").is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
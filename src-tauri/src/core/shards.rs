@@ -0,0 +1,327 @@
+// core/shards.rs
+// 按月分片的历史归档：主数据库只保存本月使用过的记录（工作集）、固定的记录和片段，
+// 更早的记录按最近使用的月份（UTC）移到 history/YYYY-MM.db，旧月份的备份和清理只涉及单个文件
+// 分片与主数据库结构相同、使用同一个密钥，查询时临时附加为 shard，由各模块合并结果
+// 归档的记录按 id 访问时（复制、固定、打标签等）或再次复制相同内容时先移回主数据库
+// 设置了到期时间的记录和带有标签规则的记录留在主数据库，由保留策略处理
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use rusqlite::{params, Connection, Params};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::core::db::{connect, now_millis, stored_key};
+
+const SHARDS_DIR: &str = "history";
+const SHARD_EXT: &str = "db";
+
+// 移动记录时复制的列，新增 items 列的迁移需要同步修改
+const ITEM_COLUMNS: &str =
+    "id, content, content_type, created_at, last_used, hash, metadata, pinned, expires_at, html, rtf";
+
+// 可以归档的记录，?1 为本月开始时间，?2 为标签规则中的标签（JSON 数组）
+const ARCHIVE_FILTER: &str = "pinned = 0 AND expires_at IS NULL AND last_used < ?1
+    AND id NOT IN (SELECT item_id FROM main.item_tags WHERE tag IN (SELECT value FROM json_each(?2)))";
+
+pub struct Shard {
+    // YYYY-MM
+    pub month: String,
+    pub path: PathBuf,
+    // 这个月的开始和结束时间（毫秒），分片中记录的 last_used 都在 [start, end) 内
+    pub start: i64,
+    pub end: i64,
+}
+
+fn month_range(month: &str) -> Option<(i64, i64)> {
+    let first = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").ok()?;
+    let next = first.checked_add_months(Months::new(1))?;
+    let millis = |date: NaiveDate| Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis());
+    Some((millis(first)?, millis(next)?))
+}
+
+fn month_start(millis: i64) -> i64 {
+    DateTime::<Utc>::from_timestamp_millis(millis)
+        .and_then(|time| time.date_naive().with_day(1))
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|time| time.and_utc().timestamp_millis())
+        .unwrap_or(millis)
+}
+
+// 分片保存在主数据库所在目录下，内存数据库没有分片
+fn shards_dir(conn: &Connection) -> Option<PathBuf> {
+    let path = conn.path().filter(|path| !path.is_empty())?;
+    Some(Path::new(path).parent()?.join(SHARDS_DIR))
+}
+
+fn shard_at(dir: &Path, month: &str) -> Option<Shard> {
+    let (start, end) = month_range(month)?;
+    Some(Shard {
+        month: month.to_string(),
+        path: dir.join(format!("{}.{}", month, SHARD_EXT)),
+        start,
+        end,
+    })
+}
+
+// 所有分片，按月份从新到旧
+pub fn list_shards(conn: &Connection) -> Vec<Shard> {
+    let Some(dir) = shards_dir(conn) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut shards: Vec<Shard> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != SHARD_EXT {
+                return None;
+            }
+            shard_at(&dir, path.file_stem()?.to_str()?)
+        })
+        .collect();
+    shards.sort_by(|a, b| b.month.cmp(&a.month));
+    shards
+}
+
+// 把分片附加为 shard 后执行 f，结束后分离
+// f 中不能使用 prepare_cached，缓存的语句会让分片无法分离
+pub fn with_shard<T>(
+    conn: &Connection,
+    shard: &Shard,
+    f: impl FnOnce() -> rusqlite::Result<T>,
+) -> rusqlite::Result<T> {
+    conn.execute(
+        "ATTACH DATABASE ?1 AS shard",
+        params![shard.path.to_string_lossy()],
+    )?;
+    let result = f();
+    conn.execute("DETACH DATABASE shard", [])?;
+    result
+}
+
+// 删除分片文件，连同异常退出时残留的 WAL 文件
+pub fn remove_shard(shard: &Shard) {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut name = shard.path.as_os_str().to_owned();
+        name.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(name));
+    }
+}
+
+// 把 select 选出的记录连同标签从 from 移到 to（main 或 shard），返回移动的记录数
+// 目标中已有相同 id 的记录（上次移动中途退出）时保留目标中的那条
+fn move_items(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+    select: &str,
+    params: impl Params,
+) -> rusqlite::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute_batch(
+        "CREATE TEMP TABLE IF NOT EXISTS moving (id INTEGER PRIMARY KEY);
+         DELETE FROM temp.moving;",
+    )?;
+    tx.execute(&format!("INSERT INTO temp.moving {}", select), params)?;
+    tx.execute(
+        &format!(
+            "INSERT OR IGNORE INTO {to}.items ({cols}) SELECT {cols} FROM {from}.items
+             WHERE id IN (SELECT id FROM temp.moving)",
+            to = to,
+            from = from,
+            cols = ITEM_COLUMNS
+        ),
+        [],
+    )?;
+    tx.execute(
+        &format!(
+            "INSERT OR IGNORE INTO {to}.item_tags (item_id, tag) SELECT item_id, tag FROM {from}.item_tags
+             WHERE item_id IN (SELECT id FROM temp.moving)",
+            to = to,
+            from = from
+        ),
+        [],
+    )?;
+    let moved = tx.execute(
+        &format!(
+            "DELETE FROM {}.items WHERE id IN (SELECT id FROM temp.moving)",
+            from
+        ),
+        [],
+    )?;
+    tx.commit()?;
+    Ok(moved)
+}
+
+// 把本月之前的记录移到对应月份的分片，返回移动的记录数；ruled 为标签规则中的标签（JSON 数组）
+pub fn archive(conn: &Connection, ruled: &str) -> Result<usize, String> {
+    let Some(dir) = shards_dir(conn) else {
+        return Ok(0);
+    };
+    let cutoff = month_start(now_millis());
+    let months: Vec<String> = conn
+        .prepare(&format!(
+            "SELECT DISTINCT strftime('%Y-%m', last_used / 1000, 'unixepoch') FROM items WHERE {}",
+            ARCHIVE_FILTER
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(params![cutoff, ruled], |row| row.get(0))?
+                .collect()
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut moved = 0;
+    for month in months {
+        let shard = shard_at(&dir, &month).ok_or("分片月份无效")?;
+        // 新分片先用主数据库的密钥建好表，附加时不指定密钥即使用主数据库的密钥
+        if !shard.path.exists() {
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            connect(&shard.path, stored_key().as_deref()).map_err(|e| e.to_string())?;
+        }
+        moved += with_shard(conn, &shard, || {
+            move_items(
+                conn,
+                "main",
+                "shard",
+                &format!(
+                    "SELECT id FROM main.items WHERE {} AND last_used >= ?3 AND last_used < ?4",
+                    ARCHIVE_FILTER
+                ),
+                params![cutoff, ruled, shard.start, shard.end],
+            )
+        })
+        .map_err(|e| e.to_string())?;
+    }
+    if moved > 0 {
+        println!("Shards - Archived {} items", moved);
+    }
+    Ok(moved)
+}
+
+// 按 id 访问归档的记录时把它移回主数据库，返回是否找到
+pub fn restore_item(conn: &Connection, id: i64) -> rusqlite::Result<bool> {
+    for shard in list_shards(conn) {
+        let moved = with_shard(conn, &shard, || {
+            move_items(
+                conn,
+                "shard",
+                "main",
+                "SELECT id FROM shard.items WHERE id = ?1",
+                params![id],
+            )
+        })?;
+        if moved > 0 {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// 依次附加每个分片执行 f，收集结果
+pub fn query_shards<T>(
+    conn: &Connection,
+    mut f: impl FnMut(&Shard) -> rusqlite::Result<Vec<T>>,
+) -> rusqlite::Result<Vec<T>> {
+    let mut results = Vec::new();
+    for shard in list_shards(conn) {
+        results.extend(with_shard(conn, &shard, || f(&shard))?);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::db::{find_by_hash, get_item, history_page, insert_item, CONTENT_TEXT};
+
+    const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+    fn temp_db(name: &str) -> (PathBuf, Connection) {
+        let dir = std::env::temp_dir().join(format!("shards-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let conn = connect(&dir.join("clipper.db"), None).unwrap();
+        (dir, conn)
+    }
+
+    fn insert_at(conn: &Connection, content: &str, last_used: i64) -> i64 {
+        let id = insert_item(conn, content, CONTENT_TEXT, content, None, None)
+            .unwrap()
+            .id;
+        conn.execute(
+            "UPDATE items SET created_at = ?1, last_used = ?1 WHERE id = ?2",
+            params![last_used, id],
+        )
+        .unwrap();
+        id
+    }
+
+    #[test]
+    fn month_ranges() {
+        assert_eq!(
+            month_range("2024-02"),
+            Some((1_706_745_600_000, 1_709_251_200_000))
+        );
+        assert_eq!(month_range("2024-13"), None);
+        assert_eq!(month_start(1_709_251_200_000 + 5), 1_709_251_200_000);
+    }
+
+    #[test]
+    fn old_items_are_archived_and_restored_on_access() {
+        let (dir, conn) = temp_db("archive");
+        let now = now_millis();
+        let current = insert_at(&conn, "current", now);
+        let last_month = insert_at(&conn, "last month", month_start(now) - DAY_MILLIS);
+        let older = insert_at(&conn, "older", month_start(now) - 40 * DAY_MILLIS);
+        let pinned = insert_at(&conn, "pinned", 0);
+        conn.execute("UPDATE items SET pinned = 1 WHERE id = ?1", params![pinned])
+            .unwrap();
+
+        assert_eq!(archive(&conn, "[]").unwrap(), 2);
+        assert_eq!(list_shards(&conn).len(), 2);
+        assert_eq!(archive(&conn, "[]").unwrap(), 0);
+
+        // 分页按最近使用时间合并主数据库和分片
+        let ids: Vec<i64> = history_page(&conn, 10, 0)
+            .unwrap()
+            .iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(ids, vec![current, last_month, older, pinned]);
+        let ids: Vec<i64> = history_page(&conn, 2, 1)
+            .unwrap()
+            .iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(ids, vec![last_month, older]);
+
+        assert_eq!(get_item(&conn, older).unwrap().unwrap().content, "older");
+        let in_main: i64 = conn
+            .query_row("SELECT COUNT(*) FROM main.items", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(in_main, 3);
+        assert_eq!(history_page(&conn, 10, 0).unwrap().len(), 4);
+
+        drop(conn);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn archived_duplicates_are_found_by_hash() {
+        let (dir, conn) = temp_db("dedup");
+        let old = insert_at(&conn, "old", month_start(now_millis()) - DAY_MILLIS);
+        assert_eq!(archive(&conn, "[]").unwrap(), 1);
+
+        assert_eq!(find_by_hash(&conn, "old").unwrap().unwrap().id, old);
+        let in_main: i64 = conn
+            .query_row("SELECT COUNT(*) FROM main.items", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(in_main, 1);
+        assert!(find_by_hash(&conn, "missing").unwrap().is_none());
+
+        drop(conn);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,176 @@
+// core/snippets.rs
+// 片段库：用户自定义的常用文本模板，与剪贴板历史分开保存在数据库的 snippets 表
+// 粘贴时替换占位符：{date} / {time}（可带格式，如 {date:%Y年%m月%d日}）、{clipboard} 为当前剪贴板文本，
+// {cursor} 为粘贴后光标停留的位置
+use chrono::Local;
+use clipboard_rs::{Clipboard, ClipboardContext};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::core::db::{now_millis, Db};
+use crate::core::hotkeys::{unbind_target, HotkeyTarget};
+use crate::core::paster::paste_text;
+use crate::core::rules::expand;
+
+const CURSOR_PLACEHOLDER: &str = "{cursor}";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub id: i64,
+    pub name: String,
+    pub content: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Snippet {
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            content: row.get("content")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+// 展开后的片段，cursor_back 为 {cursor} 之后的字符数，没有 {cursor} 时为 0
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpandedSnippet {
+    pub text: String,
+    pub cursor_back: usize,
+}
+
+pub fn get_snippet(conn: &Connection, id: i64) -> rusqlite::Result<Option<Snippet>> {
+    conn.query_row(
+        "SELECT id, name, content, created_at, updated_at FROM snippets WHERE id = ?1",
+        params![id],
+        Snippet::from_row,
+    )
+    .optional()
+}
+
+fn validate(name: &str, content: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("片段名称不能为空".into());
+    }
+    if content.is_empty() {
+        return Err("片段内容不能为空".into());
+    }
+    if content.matches(CURSOR_PLACEHOLDER).count() > 1 {
+        return Err("片段中只能有一个 {cursor}".into());
+    }
+    Ok(())
+}
+
+// 替换占位符，读取剪贴板失败时 {clipboard} 替换为空
+pub fn expand_snippet_text(content: &str) -> ExpandedSnippet {
+    let clipboard = ClipboardContext::new()
+        .and_then(|ctx| ctx.get_text())
+        .unwrap_or_default();
+    let now = Local::now();
+    let values = [("clipboard", clipboard.as_str())];
+
+    let (before, after) = content
+        .split_once(CURSOR_PLACEHOLDER)
+        .unwrap_or((content, ""));
+    let before = expand(before, &now, &values);
+    let after = expand(after, &now, &values);
+    ExpandedSnippet {
+        cursor_back: after.chars().count(),
+        text: before + &after,
+    }
+}
+
+fn load(db: &Db, id: i64) -> Result<Snippet, String> {
+    get_snippet(&db.0.lock().unwrap(), id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "片段不存在".into())
+}
+
+#[tauri::command]
+pub fn get_snippets(db: State<'_, Db>) -> Result<Vec<Snippet>, String> {
+    let conn = db.0.lock().unwrap();
+    let mut stmt = conn
+        .prepare("SELECT id, name, content, created_at, updated_at FROM snippets ORDER BY name, id")
+        .map_err(|e| e.to_string())?;
+    let snippets = stmt
+        .query_map([], Snippet::from_row)
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| e.to_string())?;
+    Ok(snippets)
+}
+
+#[tauri::command]
+pub fn create_snippet(db: State<'_, Db>, name: String, content: String) -> Result<Snippet, String> {
+    validate(&name, &content)?;
+    let conn = db.0.lock().unwrap();
+    let now = now_millis();
+    conn.execute(
+        "INSERT INTO snippets (name, content, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+        params![name.trim(), content, now],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+    drop(conn);
+    load(&db, id)
+}
+
+#[tauri::command]
+pub fn update_snippet(
+    db: State<'_, Db>,
+    id: i64,
+    name: String,
+    content: String,
+) -> Result<Snippet, String> {
+    validate(&name, &content)?;
+    let conn = db.0.lock().unwrap();
+    let updated = conn
+        .execute(
+            "UPDATE snippets SET name = ?1, content = ?2, updated_at = ?3 WHERE id = ?4",
+            params![name.trim(), content, now_millis(), id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err("片段不存在".into());
+    }
+    drop(conn);
+    load(&db, id)
+}
+
+#[tauri::command]
+pub fn delete_snippet<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    id: i64,
+) -> Result<(), String> {
+    db.0.lock()
+        .unwrap()
+        .execute("DELETE FROM snippets WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    unbind_target(&app, HotkeyTarget::Snippet(id));
+    Ok(())
+}
+
+// 预览展开后的片段，不写入剪贴板
+#[tauri::command]
+pub fn expand_snippet(db: State<'_, Db>, id: i64) -> Result<ExpandedSnippet, String> {
+    let snippet = load(&db, id)?;
+    Ok(expand_snippet_text(&snippet.content))
+}
+
+// 展开片段并粘贴到之前的前台应用，光标停在 {cursor} 的位置，命令和快捷键共用
+pub fn paste_snippet_entry<R: Runtime>(app: &AppHandle<R>, id: i64) -> Result<(), String> {
+    let snippet = load(&app.state::<Db>(), id)?;
+    let expanded = expand_snippet_text(&snippet.content);
+    paste_text(app, &expanded.text, expanded.cursor_back)
+}
+
+#[tauri::command]
+pub fn paste_snippet<R: Runtime>(app: AppHandle<R>, id: i64) -> Result<(), String> {
+    paste_snippet_entry(&app, id)
+}
@@ -0,0 +1,60 @@
+// core/source_app.rs
+// 来源应用检测：复制发生时的前台窗口（Windows/X11 为前台窗口所属进程，macOS 为最前端的应用）
+// 监听线程轮询剪贴板有最多一个轮询周期的延迟，结果只能作为参考
+use active_win_pos_rs::get_active_window;
+use std::{collections::BTreeSet, path::Path};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+#[derive(Debug, Clone)]
+pub struct SourceApp {
+    // 应用名称，例如 Code、KeePassXC
+    pub name: String,
+    // 可执行文件名（不含扩展名），例如 KeePass、1Password
+    pub process: String,
+    // 窗口标题
+    pub title: String,
+}
+
+impl SourceApp {
+    // 应用名称或可执行文件名与 entry 相同（不区分大小写）
+    pub fn matches(&self, entry: &str) -> bool {
+        let entry = entry.trim();
+        entry.eq_ignore_ascii_case(&self.name) || entry.eq_ignore_ascii_case(&self.process)
+    }
+}
+
+fn process_name(path: &Path) -> Option<String> {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+}
+
+pub fn foreground_app() -> Option<SourceApp> {
+    let window = get_active_window().ok()?;
+    Some(SourceApp {
+        name: window.app_name,
+        process: process_name(&window.process_path).unwrap_or_default(),
+        title: window.title,
+    })
+}
+
+// 列出正在运行的进程的可执行文件名，供设置页选择要排除或允许的应用
+#[tauri::command]
+pub fn get_running_apps() -> Vec<String> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+    );
+
+    let names: BTreeSet<String> = system
+        .processes()
+        .values()
+        .filter_map(|process| match process.exe() {
+            Some(exe) => process_name(exe),
+            None => process_name(Path::new(process.name())),
+        })
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.into_iter().collect()
+}
@@ -0,0 +1,183 @@
+// core/svg.rs
+// SVG 剪贴板：SVG 按文本保存矢量源码，同时用 resvg 渲染一张 PNG 预览图供列表显示，
+// 元数据 svg 字段记录预览图文件名和原始尺寸；可以复制为 SVG 源码，也可以按指定宽度渲染为 PNG 复制
+use clipboard_rs::{
+    common::RustImage, Clipboard, ClipboardContent, ClipboardContext, ContentFormat, RustImageData,
+};
+use resvg::{
+    tiny_skia::{Pixmap, Transform},
+    usvg::{fontdb, Options, Tree},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    fs,
+    sync::{Arc, OnceLock},
+};
+use tauri::{AppHandle, Runtime};
+
+use crate::core::db::{ClipItem, CONTENT_TEXT};
+use crate::core::images::{images_dir, thumbnail_name};
+use crate::core::sensitive::get_revealed_item;
+
+// 各平台剪贴板中 SVG 的格式名称
+#[cfg(target_os = "macos")]
+const SVG_FORMAT: &str = "public.svg-image";
+#[cfg(not(target_os = "macos"))]
+const SVG_FORMAT: &str = "image/svg+xml";
+// 更大的文本不尝试按 SVG 解析
+const MAX_SVG_BYTES: usize = 4 * 1024 * 1024;
+// 预览图的最长边
+const PREVIEW_SIZE: f32 = 512.0;
+// 渲染为 PNG 时允许的最大宽度
+const MAX_PNG_WIDTH: u32 = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SvgFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+// 系统字体只加载一次，渲染 SVG 中的文字时使用
+fn options() -> Options<'static> {
+    static FONTS: OnceLock<Arc<fontdb::Database>> = OnceLock::new();
+    let fonts = FONTS.get_or_init(|| {
+        let mut fonts = fontdb::Database::new();
+        fonts.load_system_fonts();
+        Arc::new(fonts)
+    });
+    Options {
+        fontdb: fonts.clone(),
+        ..Options::default()
+    }
+}
+
+fn looks_like_svg(text: &str) -> bool {
+    let text = text.trim_start();
+    text.len() <= MAX_SVG_BYTES
+        && (text.starts_with("<svg") || (text.starts_with("<?xml") && text.contains("<svg")))
+}
+
+fn parse(source: &str) -> Result<Tree, String> {
+    Tree::from_str(source, &options()).map_err(|e| e.to_string())
+}
+
+// 按宽度等比渲染为 PNG
+fn render_png(tree: &Tree, width: u32) -> Result<Vec<u8>, String> {
+    let size = tree.size();
+    let scale = width as f32 / size.width();
+    let height = (size.height() * scale).ceil().max(1.0) as u32;
+    let mut pixmap = Pixmap::new(width, height).ok_or("图片尺寸无效")?;
+    resvg::render(
+        tree,
+        Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap.encode_png().map_err(|e| e.to_string())
+}
+
+// 读取剪贴板中的 SVG 格式（设计工具复制的矢量图），没有时返回 None
+pub fn read_svg(ctx: &ClipboardContext) -> Option<String> {
+    if !ctx.has(ContentFormat::Other(SVG_FORMAT.into())) {
+        return None;
+    }
+    let data = ctx.get_buffer(SVG_FORMAT).ok()?;
+    String::from_utf8(data)
+        .ok()
+        .filter(|source| looks_like_svg(source))
+}
+
+// 文本是 SVG 源码时渲染预览图，并在元数据中加入 svg 字段；不是 SVG 或无法解析时原样返回
+pub fn svg_metadata<R: Runtime>(
+    app: &AppHandle<R>,
+    text: &str,
+    hash: &str,
+    metadata: Option<Value>,
+) -> Option<Value> {
+    if !looks_like_svg(text) {
+        return metadata;
+    }
+    let preview = thumbnail_name(hash);
+    let rendered = parse(text).and_then(|tree| {
+        let size = tree.size();
+        let scale = (PREVIEW_SIZE / size.width().max(size.height())).min(1.0);
+        let png = render_png(&tree, (size.width() * scale).ceil().max(1.0) as u32)?;
+        fs::write(images_dir(app)?.join(&preview), png).map_err(|e| e.to_string())?;
+        Ok(size)
+    });
+    match rendered {
+        Ok(size) => {
+            let mut metadata = metadata.unwrap_or_else(|| json!({}));
+            metadata["svg"] = json!({
+                "preview": preview,
+                "width": size.width(),
+                "height": size.height(),
+            });
+            Some(metadata)
+        }
+        Err(e) => {
+            println!("Svg - Failed to render preview: {}", e);
+            metadata
+        }
+    }
+}
+
+pub fn has_svg(metadata: Option<&Value>) -> bool {
+    metadata.is_some_and(|metadata| metadata.get("svg").is_some())
+}
+
+// 以 SVG 格式写回剪贴板，同时写入源码文本和预览图，不支持 SVG 的应用仍可粘贴
+pub fn write_svg<R: Runtime>(
+    app: &AppHandle<R>,
+    ctx: &ClipboardContext,
+    item: &ClipItem,
+) -> Result<(), String> {
+    let mut contents = vec![
+        ClipboardContent::Text(item.content.clone()),
+        ClipboardContent::Other(SVG_FORMAT.into(), item.content.as_bytes().to_vec()),
+    ];
+    let preview = item
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata["svg"]["preview"].as_str())
+        .map(|preview| images_dir(app).map(|dir| dir.join(preview)))
+        .transpose()?;
+    if let Some(image) =
+        preview.and_then(|path| RustImageData::from_path(&path.to_string_lossy()).ok())
+    {
+        contents.push(ClipboardContent::Image(image));
+    }
+    ctx.set(contents).map_err(|e| e.to_string())
+}
+
+// 复制 SVG 记录：Svg 写入矢量源码，Png 按 width 渲染（默认为原始宽度）后作为图片写入
+#[tauri::command]
+pub fn copy_svg<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    format: Option<SvgFormat>,
+    width: Option<u32>,
+    passcode: Option<String>,
+) -> Result<(), String> {
+    let item = get_revealed_item(&app, id, passcode.as_deref())?;
+    if item.content_type != CONTENT_TEXT || !has_svg(item.metadata.as_ref()) {
+        return Err("该记录不是 SVG".into());
+    }
+
+    let ctx = ClipboardContext::new().map_err(|e| e.to_string())?;
+    match format.unwrap_or_default() {
+        SvgFormat::Svg => write_svg(&app, &ctx, &item),
+        SvgFormat::Png => {
+            let tree = parse(&item.content)?;
+            let width = width.unwrap_or_else(|| tree.size().width().ceil() as u32);
+            if width == 0 || width > MAX_PNG_WIDTH {
+                return Err("图片尺寸无效".into());
+            }
+            let png = render_png(&tree, width)?;
+            let image = RustImageData::from_bytes(&png).map_err(|e| e.to_string())?;
+            ctx.set_image(image).map_err(|e| e.to_string())
+        }
+    }
+}
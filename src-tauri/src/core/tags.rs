@@ -0,0 +1,107 @@
+// core/tags.rs
+// 标签：一条记录可以有多个标签，保留策略可以按标签单独设置保留时间（见 retention.rs）
+// 标签去掉首尾空白并转为小写后保存
+use rusqlite::{params, Connection};
+use std::collections::BTreeSet;
+use tauri::{AppHandle, Runtime, State};
+
+use crate::core::db::{get_item, Db};
+use crate::core::lock::AppLock;
+use crate::core::retention::run_cleanup;
+use crate::core::shards::query_shards;
+
+const MAX_TAG_LEN: usize = 64;
+
+pub fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err("标签不能为空".into());
+    }
+    if tag.chars().count() > MAX_TAG_LEN {
+        return Err(format!("标签不能超过 {} 个字符", MAX_TAG_LEN));
+    }
+    Ok(tag)
+}
+
+pub fn tag_item(conn: &Connection, id: i64, tag: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO item_tags (item_id, tag) VALUES (?1, ?2)",
+        params![id, tag],
+    )?;
+    Ok(())
+}
+
+// 修改标签后按新标签的保留规则清理，返回记录现在的标签
+fn tags_changed<R: Runtime>(app: &AppHandle<R>, db: &Db, id: i64) -> Result<Vec<String>, String> {
+    let item = get_item(&db.0.lock().unwrap(), id)
+        .map_err(|e| e.to_string())?
+        .ok_or("记录不存在")?;
+    if let Err(e) = run_cleanup(app) {
+        println!("Tags - Cleanup failed: {}", e);
+    }
+    Ok(item.tags)
+}
+
+#[tauri::command]
+pub fn add_tag<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    id: i64,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    lock.ensure_unlocked()?;
+    let tag = normalize_tag(&tag)?;
+    {
+        let conn = db.0.lock().unwrap();
+        if get_item(&conn, id).map_err(|e| e.to_string())?.is_none() {
+            return Err("记录不存在".into());
+        }
+        tag_item(&conn, id, &tag).map_err(|e| e.to_string())?;
+    }
+    tags_changed(&app, &db, id)
+}
+
+#[tauri::command]
+pub fn remove_tag<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, Db>,
+    lock: State<'_, AppLock>,
+    id: i64,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    lock.ensure_unlocked()?;
+    let tag = normalize_tag(&tag)?;
+    {
+        let conn = db.0.lock().unwrap();
+        // 归档的记录由 get_item 先移回主数据库
+        if get_item(&conn, id).map_err(|e| e.to_string())?.is_none() {
+            return Err("记录不存在".into());
+        }
+        conn.execute(
+            "DELETE FROM item_tags WHERE item_id = ?1 AND tag = ?2",
+            params![id, tag],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tags_changed(&app, &db, id)
+}
+
+fn distinct_tags(conn: &Connection, schema: &str) -> rusqlite::Result<Vec<String>> {
+    conn.prepare(&format!("SELECT DISTINCT tag FROM {}.item_tags", schema))?
+        .query_map([], |row| row.get(0))?
+        .collect()
+}
+
+// 所有用过的标签（包括归档的记录），按名称排序
+#[tauri::command]
+pub fn get_all_tags(db: State<'_, Db>, lock: State<'_, AppLock>) -> Result<Vec<String>, String> {
+    lock.ensure_unlocked()?;
+    let conn = db.0.lock().unwrap();
+    let mut tags: BTreeSet<String> = distinct_tags(&conn, "main")
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    tags.extend(query_shards(&conn, |_| distinct_tags(&conn, "shard")).map_err(|e| e.to_string())?);
+    Ok(tags.into_iter().collect())
+}
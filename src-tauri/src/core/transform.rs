@@ -0,0 +1,280 @@
+// core/transform.rs
+// 文本转换：对文本记录依次执行大小写转换、去除空白、JSON 格式化、Base64/URL 编解码等操作，
+// 结果保存为一条新记录，或直接写入剪贴板
+// 设置中的自动转换规则在复制文本时按顺序执行（例如去掉链接中的跟踪参数），保存的是转换后的文本
+use base64::{engine::general_purpose::STANDARD, Engine};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+
+use crate::core::clipboard_watcher::{ingest_text, write_text};
+use crate::core::db::{ClipItem, CONTENT_TEXT};
+use crate::core::sensitive::{conceal, get_revealed_item};
+use crate::core::settings::current_settings;
+
+// URL 编码时保留 RFC 3986 中的非保留字符
+const URL_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransformOp {
+    Uppercase,
+    Lowercase,
+    TitleCase,
+    Trim,
+    // 连续的空白（包括换行）合并为一个空格
+    CollapseWhitespace,
+    JsonPretty,
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum TransformAction {
+    // 把所有匹配的部分替换为 replacement，可以用 $1、${name} 引用捕获组
+    Replace { replacement: String },
+    // 文本匹配时对整段文本依次执行转换
+    Normalize { ops: Vec<TransformOp> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformRule {
+    pub name: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    // 正则表达式，为空时匹配所有文本
+    #[serde(default)]
+    pub pattern: String,
+    pub action: TransformAction,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+// 保存设置前检查所有自动转换规则
+pub fn validate_transform_rules(rules: &[TransformRule]) -> Result<(), String> {
+    for rule in rules {
+        Regex::new(&rule.pattern)
+            .map_err(|e| format!("转换规则“{}”的正则表达式无效: {}", rule.name, e))?;
+        if let TransformAction::Normalize { ops } = &rule.action {
+            if ops.is_empty() {
+                return Err(format!("转换规则“{}”没有设置转换操作", rule.name));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_rule(text: &str, rule: &TransformRule) -> Result<Option<String>, String> {
+    let regex = Regex::new(&rule.pattern).map_err(|e| e.to_string())?;
+    if !regex.is_match(text) {
+        return Ok(None);
+    }
+    match &rule.action {
+        TransformAction::Replace { replacement } => Ok(Some(
+            regex.replace_all(text, replacement.as_str()).into_owned(),
+        )),
+        TransformAction::Normalize { ops } => ops
+            .iter()
+            .try_fold(text.to_string(), |text, &op| apply(&text, op))
+            .map(Some),
+    }
+}
+
+// 按顺序执行启用的自动转换规则，文本有变化时返回转换后的文本和生效的规则名称
+// 某条规则执行失败（例如不是有效的 JSON）时跳过该规则
+pub fn apply_transform_rules(text: &str, rules: &[TransformRule]) -> Option<(String, Vec<String>)> {
+    let mut current = text.to_string();
+    let mut applied = Vec::new();
+    for rule in rules.iter().filter(|rule| rule.enabled) {
+        match apply_rule(&current, rule) {
+            Ok(Some(transformed)) if transformed != current => {
+                current = transformed;
+                applied.push(rule.name.clone());
+            }
+            Ok(_) => {}
+            Err(e) => println!("Transform - Rule {} failed: {}", rule.name, e),
+        }
+    }
+    (!applied.is_empty()).then_some((current, applied))
+}
+
+// 每个单词首字母大写，其余小写
+fn title_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut word_start = true;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            if word_start {
+                result.extend(c.to_uppercase());
+            } else {
+                result.extend(c.to_lowercase());
+            }
+            word_start = false;
+        } else {
+            result.push(c);
+            word_start = !matches!(c, '\'' | '’');
+        }
+    }
+    result
+}
+
+pub fn apply(text: &str, op: TransformOp) -> Result<String, String> {
+    match op {
+        TransformOp::Uppercase => Ok(text.to_uppercase()),
+        TransformOp::Lowercase => Ok(text.to_lowercase()),
+        TransformOp::TitleCase => Ok(title_case(text)),
+        TransformOp::Trim => Ok(text.trim().to_string()),
+        TransformOp::CollapseWhitespace => {
+            Ok(text.split_whitespace().collect::<Vec<_>>().join(" "))
+        }
+        TransformOp::JsonPretty => serde_json::from_str::<Value>(text)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .map_err(|e| format!("不是有效的 JSON: {}", e)),
+        TransformOp::Base64Encode => Ok(STANDARD.encode(text)),
+        TransformOp::Base64Decode => {
+            let compact: String = text.split_whitespace().collect();
+            let bytes = STANDARD
+                .decode(compact)
+                .map_err(|e| format!("不是有效的 Base64: {}", e))?;
+            String::from_utf8(bytes).map_err(|_| "解码结果不是文本".into())
+        }
+        TransformOp::UrlEncode => Ok(utf8_percent_encode(text, URL_ENCODE_SET).to_string()),
+        TransformOp::UrlDecode => percent_decode_str(text)
+            .decode_utf8()
+            .map(|decoded| decoded.into_owned())
+            .map_err(|_| "解码结果不是文本".into()),
+    }
+}
+
+// 按 ops 的顺序转换文本记录；copy 为 true 时写入剪贴板并返回 None，否则保存为新记录并返回
+#[tauri::command]
+pub fn transform_item<R: Runtime>(
+    app: AppHandle<R>,
+    id: i64,
+    ops: Vec<TransformOp>,
+    copy: Option<bool>,
+    passcode: Option<String>,
+) -> Result<Option<ClipItem>, String> {
+    if ops.is_empty() {
+        return Err("没有需要执行的操作".into());
+    }
+    let item = get_revealed_item(&app, id, passcode.as_deref())?;
+    if item.content_type != CONTENT_TEXT {
+        return Err("只能转换文本记录".into());
+    }
+
+    let text = ops
+        .iter()
+        .try_fold(item.content, |text, &op| apply(&text, op))?;
+    if text.is_empty() {
+        return Err("转换结果为空".into());
+    }
+    if copy.unwrap_or(false) {
+        write_text(&text)?;
+        return Ok(None);
+    }
+
+    // 转换结果同样经过敏感内容识别，被拒绝保存时返回错误
+    let metadata = json!({ "transformed": { "from": id, "ops": ops } });
+    let mut item = ingest_text(&app, &text, Some(metadata)).ok_or("转换后的内容未被保存")?;
+    conceal(&mut item, &current_settings(&app).capture.sensitive);
+    Ok(Some(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, pattern: &str, action: TransformAction) -> TransformRule {
+        TransformRule {
+            name: name.into(),
+            enabled: true,
+            pattern: pattern.into(),
+            action,
+        }
+    }
+
+    fn replace(replacement: &str) -> TransformAction {
+        TransformAction::Replace {
+            replacement: replacement.into(),
+        }
+    }
+
+    #[test]
+    fn rules_run_in_order() {
+        let rules = [
+            rule("strip", r"\?utm_\w+=\w+", replace("")),
+            rule(
+                "upper",
+                "",
+                TransformAction::Normalize {
+                    ops: vec![TransformOp::Uppercase],
+                },
+            ),
+        ];
+        assert_eq!(
+            apply_transform_rules("https://a.com/x?utm_source=mail", &rules),
+            Some((
+                "HTTPS://A.COM/X".to_string(),
+                vec!["strip".to_string(), "upper".to_string()]
+            ))
+        );
+    }
+
+    #[test]
+    fn disabled_and_unchanged_rules_are_not_reported() {
+        let mut disabled = rule("disabled", "", replace("x"));
+        disabled.enabled = false;
+        let rules = [
+            disabled,
+            rule("no-match", r"\d+", replace("#")),
+            rule("same", "abc", replace("abc")),
+        ];
+        assert_eq!(apply_transform_rules("abc", &rules), None);
+    }
+
+    #[test]
+    fn failing_rules_are_skipped() {
+        let rules = [
+            rule(
+                "json",
+                "",
+                TransformAction::Normalize {
+                    ops: vec![TransformOp::JsonPretty],
+                },
+            ),
+            rule(
+                "trim",
+                "",
+                TransformAction::Normalize {
+                    ops: vec![TransformOp::Trim],
+                },
+            ),
+        ];
+        assert_eq!(
+            apply_transform_rules(" {not json ", &rules),
+            Some(("{not json".to_string(), vec!["trim".to_string()]))
+        );
+    }
+
+    #[test]
+    fn replacement_can_use_capture_groups() {
+        let rules = [rule("swap", r"(\w+)@(\w+)", replace("$2/$1"))];
+        assert_eq!(
+            apply_transform_rules("user@host", &rules),
+            Some(("host/user".to_string(), vec!["swap".to_string()]))
+        );
+    }
+}
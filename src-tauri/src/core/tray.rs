@@ -1,16 +1,145 @@
 // core/tray.rs
+use std::path::Path;
 use tauri::{
-    menu::{Menu, MenuItem},
+    image::Image,
+    menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Emitter, Manager, Runtime,
+    AppHandle, Emitter, Manager, Runtime, Theme,
 };
 
-pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
+use crate::core::appearance::{Appearance, TrayIconStyle};
+use crate::core::clipboard_watcher::{is_watcher_running, resume_watcher};
+use crate::core::db::{history_page, ClipItem, Db, CONTENT_FILES, CONTENT_IMAGE};
+use crate::core::lock::{lock_from_tray, AppLock};
+use crate::core::paster::paste_entry;
+use crate::core::sensitive::conceal;
+use crate::core::settings::current_settings;
+
+// 最近记录菜单项的 id 前缀，后接记录 id
+const CLIP_MENU_PREFIX: &str = "clip:";
+// 最近记录菜单项显示的最大字符数
+const CLIP_LABEL_CHARS: usize = 40;
+
+// 菜单项文字：文本合并空白后截断，图片和文件列表显示类型和文件名
+fn clip_label(item: &ClipItem) -> String {
+    let label = match item.content_type.as_str() {
+        CONTENT_IMAGE => "[图片]".to_string(),
+        CONTENT_FILES => {
+            let paths: Vec<String> = serde_json::from_str(&item.content).unwrap_or_default();
+            let names: Vec<_> = paths
+                .iter()
+                .map(|path| {
+                    Path::new(path)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| path.clone())
+                })
+                .collect();
+            format!("[文件] {}", names.join(", "))
+        }
+        _ => item
+            .content
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+
+    let mut truncated: String = label.chars().take(CLIP_LABEL_CHARS).collect();
+    if truncated.len() < label.len() {
+        truncated.push('…');
+    }
+    // 菜单文字中的 & 表示助记符，需要转义才能原样显示
+    truncated.replace('&', "&&")
+}
+
+// 最近记录在上，分隔线下是固定的显示/隐藏/锁定/恢复记录/退出
+fn build_menu<R: Runtime>(app: &AppHandle<R>, recent_items: &[ClipItem]) -> tauri::Result<Menu<R>> {
     let quit_i = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
     let show_i = MenuItem::with_id(app, "show", "显示", true, None::<&str>)?;
     let hide_i = MenuItem::with_id(app, "hide", "隐藏", true, None::<&str>)?;
+    // 设置了锁定密码且未锁定时才能从托盘锁定
+    let lock = app.state::<AppLock>();
+    let lock_i = MenuItem::with_id(
+        app,
+        "lock",
+        "锁定",
+        lock.is_enabled() && !lock.is_locked(),
+        None::<&str>,
+    )?;
+    // 监听被停止（如检测到异常后自动暂停）时从托盘恢复，锁定期间不能恢复
+    let resume_i = MenuItem::with_id(
+        app,
+        "resume",
+        "恢复记录",
+        !is_watcher_running(app) && !lock.is_locked(),
+        None::<&str>,
+    )?;
 
-    let menu = Menu::with_items(app, &[&show_i, &hide_i, &quit_i])?;
+    let menu = Menu::new(app)?;
+    if !recent_items.is_empty() {
+        for item in recent_items {
+            let id = format!("{}{}", CLIP_MENU_PREFIX, item.id);
+            menu.append(&MenuItem::with_id(
+                app,
+                id,
+                clip_label(item),
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    menu.append_items(&[&show_i, &hide_i, &lock_i, &resume_i, &quit_i])?;
+    Ok(menu)
+}
+
+// 用给定的最近记录重建托盘菜单
+pub fn rebuild_tray_menu<R: Runtime>(app: &AppHandle<R>, recent_items: &[ClipItem]) {
+    let Some(tray) = app.tray_by_id("tray") else {
+        println!("Tray not found, skip rebuilding menu");
+        return;
+    };
+    let menu = match build_menu(app, recent_items) {
+        Ok(menu) => menu,
+        Err(e) => {
+            println!("Failed to build tray menu: {:?}", e);
+            return;
+        }
+    };
+    if let Err(e) = tray.set_menu(Some(menu)) {
+        println!("Failed to set tray menu: {:?}", e);
+    }
+}
+
+// 从数据库读取最近记录并重建托盘菜单，历史变化后调用，调用时不能持有数据库锁
+// 应用锁定时不显示最近记录
+pub fn refresh_tray_menu<R: Runtime>(app: &AppHandle<R>) {
+    let count = current_settings(app).tray_recent_items;
+    let recent_items = if count == 0 || app.state::<AppLock>().is_locked() {
+        Vec::new()
+    } else {
+        let db = app.state::<Db>();
+        let items = history_page(&db.0.lock().unwrap(), count, 0);
+        match items {
+            Ok(mut items) => {
+                let sensitive = current_settings(app).capture.sensitive;
+                for item in &mut items {
+                    conceal(item, &sensitive);
+                }
+                items
+            }
+            Err(e) => {
+                println!("Failed to load recent items for tray: {:?}", e);
+                return;
+            }
+        }
+    };
+    rebuild_tray_menu(app, &recent_items);
+}
+
+pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
+    // 数据库打开后再由 refresh_tray_menu 加入最近记录
+    let menu = build_menu(app, &[])?;
 
     let _ = TrayIconBuilder::with_id("tray")
         .icon(app.default_window_icon().unwrap().clone())
@@ -77,6 +206,16 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
                         }
                     }
                 }
+                "lock" => {
+                    println!("Lock menu clicked");
+                    lock_from_tray(app);
+                }
+                "resume" => {
+                    println!("Resume menu clicked");
+                    if !app.state::<AppLock>().is_locked() && resume_watcher(app) {
+                        refresh_tray_menu(app);
+                    }
+                }
                 "hide" => {
                     println!("Hide menu clicked");
                     match app.get_webview_window("main") {
@@ -89,9 +228,18 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
                         }
                     }
                 }
-                _ => {
-                    println!("Unknown menu item clicked: {:?}", event.id);
-                }
+                // 点击最近记录时直接粘贴到当前前台应用
+                id => match id
+                    .strip_prefix(CLIP_MENU_PREFIX)
+                    .and_then(|id| id.parse().ok())
+                {
+                    Some(id) => {
+                        if let Err(e) = paste_entry(app, id, None, None) {
+                            println!("Failed to paste item from tray: {}", e);
+                        }
+                    }
+                    None => println!("Unknown menu item clicked: {:?}", event.id),
+                },
             }
         })
         .on_tray_icon_event(|tray, event| {
@@ -141,3 +289,87 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
 
     Ok(())
 }
+
+// 根据外观设置和当前托盘主题重新加载托盘图标，主题或设置变化时调用
+pub fn reload_tray_icon<R: Runtime>(app: &AppHandle<R>, appearance: &Appearance) {
+    let Some(tray) = app.tray_by_id("tray") else {
+        println!("Tray not found, skip reloading icon");
+        return;
+    };
+    let Some(default_icon) = app.default_window_icon() else {
+        return;
+    };
+
+    let (icon, as_template) = match appearance.tray_icon {
+        TrayIconStyle::Auto => (monochrome_icon(default_icon, tray_theme(app)), true),
+        TrayIconStyle::Color => (default_icon.clone().to_owned(), false),
+        TrayIconStyle::Custom => match appearance.custom_tray_icon.as_deref().map(Image::from_path)
+        {
+            Some(Ok(image)) => (image, false),
+            Some(Err(e)) => {
+                println!("Failed to load custom tray icon: {:?}", e);
+                (default_icon.clone().to_owned(), false)
+            }
+            None => (default_icon.clone().to_owned(), false),
+        },
+    };
+
+    // macOS 上单色图标作为模板图标，由系统根据菜单栏外观自动着色
+    if let Err(e) = tray.set_icon_with_as_template(Some(icon), as_template) {
+        println!("Failed to set tray icon: {:?}", e);
+    }
+}
+
+// 更新托盘提示文字，白名单模式下明确提示只记录部分应用
+pub fn update_tray_tooltip<R: Runtime>(app: &AppHandle<R>, allowlist: bool) {
+    let Some(tray) = app.tray_by_id("tray") else {
+        println!("Tray not found, skip updating tooltip");
+        return;
+    };
+    let tooltip = if allowlist {
+        "Clipper（白名单模式：仅记录允许的应用）"
+    } else {
+        "Clipper"
+    };
+    if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+        println!("Failed to set tray tooltip: {:?}", e);
+    }
+}
+
+// 用应用图标的轮廓生成单色图标：深色托盘用白色，浅色托盘用黑色
+fn monochrome_icon(source: &Image<'_>, theme: Theme) -> Image<'static> {
+    let value = if theme == Theme::Dark { 255 } else { 0 };
+    let rgba = source
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|pixel| [value, value, value, pixel[3]])
+        .collect();
+    Image::new_owned(rgba, source.width(), source.height())
+}
+
+// 检测托盘（任务栏/菜单栏）的主题，Windows 上任务栏主题可以与应用主题不同
+fn tray_theme<R: Runtime>(app: &AppHandle<R>) -> Theme {
+    #[cfg(target_os = "windows")]
+    if let Some(theme) = windows_taskbar_theme() {
+        return theme;
+    }
+
+    app.get_webview_window("main")
+        .and_then(|window| window.theme().ok())
+        .unwrap_or(Theme::Light)
+}
+
+#[cfg(target_os = "windows")]
+fn windows_taskbar_theme() -> Option<Theme> {
+    use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+    let personalize = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize")
+        .ok()?;
+    let light: u32 = personalize.get_value("SystemUsesLightTheme").ok()?;
+    Some(if light == 0 {
+        Theme::Dark
+    } else {
+        Theme::Light
+    })
+}
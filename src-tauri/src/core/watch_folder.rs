@@ -0,0 +1,231 @@
+// core/watch_folder.rs
+// 监视文件夹：放入监视文件夹的新文本文件和图片（例如手机同步文件夹、扫描仪的输出目录）自动保存为历史记录
+// 每个文件夹可以设置给新记录添加的标签，以及保存后是否删除源文件；只监视文件夹本身，不包括子文件夹
+use clipboard_rs::{common::RustImage, RustImageData};
+use notify::{
+    event::{CreateKind, ModifyKind, RenameMode},
+    recommended_watcher, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{mpsc, Mutex},
+    thread,
+    time::Duration,
+};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::core::clipboard_watcher::{ingest_image, ingest_text};
+use crate::core::db::{ClipItem, Db};
+use crate::core::exif::open_oriented;
+use crate::core::settings::current_settings;
+use crate::core::tags::{normalize_tag, tag_item};
+
+const TEXT_EXTENSIONS: &[&str] = &["txt", "md", "markdown", "csv", "json", "log"];
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp"];
+// 文件出现后等待写入完成再读取，同步工具和扫描仪通常分多次写入
+const SETTLE_DELAY: Duration = Duration::from_secs(1);
+// 更大的文本文件不保存
+const MAX_TEXT_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WatchFolder {
+    pub path: String,
+    pub enabled: bool,
+    // 给从该文件夹保存的记录添加的标签
+    pub tags: Vec<String>,
+    // 保存为记录后删除源文件
+    pub delete_source: bool,
+}
+
+impl Default for WatchFolder {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            enabled: true,
+            tags: Vec::new(),
+            delete_source: false,
+        }
+    }
+}
+
+// 正在运行的文件监视，替换或清空时旧的监视随之停止
+#[derive(Default)]
+pub struct WatchFolderState {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+pub fn validate_watch_folders(folders: &[WatchFolder]) -> Result<(), String> {
+    for folder in folders.iter().filter(|folder| folder.enabled) {
+        let path = Path::new(folder.path.trim());
+        if !path.is_absolute() || !path.is_dir() {
+            return Err(format!("监视文件夹不存在: {}", folder.path));
+        }
+        for tag in &folder.tags {
+            normalize_tag(tag)?;
+        }
+    }
+    Ok(())
+}
+
+// 新建的文件，或从其他位置移入的文件
+fn is_new_file(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(CreateKind::File | CreateKind::Any)
+            | EventKind::Modify(ModifyKind::Name(RenameMode::To | RenameMode::Any))
+    )
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+}
+
+// 跳过隐藏文件和下载、同步过程中的临时文件
+fn is_ignored(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    name.starts_with('.')
+        || name.starts_with('~')
+        || matches!(
+            extension(path).as_deref(),
+            Some("tmp" | "part" | "crdownload")
+        )
+}
+
+fn read_text(path: &Path) -> Result<Option<String>, String> {
+    let size = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_TEXT_BYTES {
+        return Err(format!("文件过大（{} 字节）", size));
+    }
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Ok(Some(text).filter(|text| !text.trim().is_empty()))
+}
+
+fn ingest<R: Runtime>(
+    app: &AppHandle<R>,
+    folder: &WatchFolder,
+    path: &Path,
+) -> Result<Option<ClipItem>, String> {
+    let extension = extension(path).unwrap_or_default();
+    if TEXT_EXTENSIONS.contains(&extension.as_str()) {
+        let Some(text) = read_text(path)? else {
+            return Ok(None);
+        };
+        // 源文件保留时记录来源路径，可以从记录打开
+        let metadata = (!folder.delete_source)
+            .then(|| json!({ "origin": { "path": path.to_string_lossy() } }));
+        Ok(ingest_text(app, &text, metadata))
+    } else if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        // 只保存像素，按 EXIF 方向旋转后元数据不再需要
+        let image = RustImageData::from_dynamic_image(open_oriented(path)?);
+        ingest_image(app, &image, None)
+    } else {
+        Ok(None)
+    }
+}
+
+// 保存一个新文件，按文件夹的设置添加标签并删除源文件
+fn handle_file<R: Runtime>(app: &AppHandle<R>, folders: &[WatchFolder], path: &Path) {
+    if !path.is_file() || is_ignored(path) {
+        return;
+    }
+    let Some(folder) = folders
+        .iter()
+        .find(|folder| path.parent() == Some(Path::new(folder.path.trim())))
+    else {
+        return;
+    };
+
+    let item = match ingest(app, folder, path) {
+        Ok(Some(item)) => item,
+        Ok(None) => return,
+        Err(e) => {
+            println!("Watch folder - Failed to import {:?}: {}", path, e);
+            return;
+        }
+    };
+    println!("Watch folder - Imported {:?}", path);
+
+    if !folder.tags.is_empty() {
+        let db = app.state::<Db>();
+        let conn = db.0.lock().unwrap();
+        for tag in folder.tags.iter().filter_map(|tag| normalize_tag(tag).ok()) {
+            if let Err(e) = tag_item(&conn, item.id, &tag) {
+                println!("Watch folder - Failed to tag item: {:?}", e);
+            }
+        }
+    }
+    if folder.delete_source {
+        if let Err(e) = fs::remove_file(path) {
+            println!("Watch folder - Failed to remove {:?}: {:?}", path, e);
+        }
+    }
+}
+
+fn start_watcher<R: Runtime>(
+    app: &AppHandle<R>,
+    folders: Vec<WatchFolder>,
+) -> notify::Result<RecommendedWatcher> {
+    let (sender, receiver) = mpsc::channel::<PathBuf>();
+    let mut watcher = recommended_watcher(move |event: notify::Result<Event>| match event {
+        Ok(event) if is_new_file(&event.kind) => {
+            for path in event.paths {
+                let _ = sender.send(path);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => println!("Watch folder - Watch error: {:?}", e),
+    })?;
+    for folder in &folders {
+        if let Err(e) = watcher.watch(Path::new(folder.path.trim()), RecursiveMode::NonRecursive) {
+            println!("Watch folder - Failed to watch {}: {:?}", folder.path, e);
+        }
+    }
+
+    // 监视停止后发送端随之释放，处理线程自动退出
+    let app_handle = app.clone();
+    thread::spawn(move || {
+        while let Ok(path) = receiver.recv() {
+            thread::sleep(SETTLE_DELAY);
+            let mut paths = BTreeSet::from([path]);
+            paths.extend(receiver.try_iter());
+            for path in paths {
+                handle_file(&app_handle, &folders, &path);
+            }
+        }
+    });
+    Ok(watcher)
+}
+
+// 按设置重新开始监视，没有启用的文件夹时停止监视
+pub fn apply_watch_folders<R: Runtime>(app: &AppHandle<R>, folders: &[WatchFolder]) {
+    let state = app.state::<WatchFolderState>();
+    let mut watcher = state.watcher.lock().unwrap();
+    *watcher = None;
+
+    let enabled: Vec<WatchFolder> = folders
+        .iter()
+        .filter(|folder| folder.enabled && !folder.path.trim().is_empty())
+        .cloned()
+        .collect();
+    if enabled.is_empty() {
+        return;
+    }
+    match start_watcher(app, enabled) {
+        Ok(started) => *watcher = Some(started),
+        Err(e) => println!("Watch folder - Failed to start watcher: {:?}", e),
+    }
+}
+
+// 启动时开始监视设置中的文件夹，需要在数据库打开之后调用
+pub fn setup_watch_folders<R: Runtime>(app: &AppHandle<R>) {
+    apply_watch_folders(app, &current_settings(app).watch_folders);
+}
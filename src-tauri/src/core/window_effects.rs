@@ -0,0 +1,84 @@
+// core/window_effects.rs
+// 窗口半透明效果：Windows 上的 Mica/Acrylic，macOS 上的 Vibrancy
+// 按偏好顺序逐个尝试，平台或系统版本不支持时回退，最终保持普通窗口
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, Theme, WebviewWindow};
+use window_vibrancy::{NSVisualEffectMaterial, NSVisualEffectState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowEffect {
+    #[default]
+    Off,
+    // 自动选择当前平台上可用的最佳效果
+    Auto,
+    Mica,
+    Acrylic,
+    Vibrancy,
+}
+
+impl WindowEffect {
+    // 该设置下依次尝试的效果
+    fn candidates(self) -> &'static [WindowEffect] {
+        match self {
+            WindowEffect::Off => &[],
+            WindowEffect::Auto => &[
+                WindowEffect::Mica,
+                WindowEffect::Acrylic,
+                WindowEffect::Vibrancy,
+            ],
+            WindowEffect::Mica => &[WindowEffect::Mica, WindowEffect::Acrylic],
+            WindowEffect::Acrylic => &[WindowEffect::Acrylic],
+            WindowEffect::Vibrancy => &[WindowEffect::Vibrancy],
+        }
+    }
+}
+
+fn try_apply<R: Runtime>(
+    window: &WebviewWindow<R>,
+    effect: WindowEffect,
+    theme: Theme,
+) -> Result<(), window_vibrancy::Error> {
+    match effect {
+        WindowEffect::Mica => window_vibrancy::apply_mica(window, Some(theme == Theme::Dark)),
+        WindowEffect::Acrylic => {
+            let tint = if theme == Theme::Dark {
+                (18, 18, 18, 125)
+            } else {
+                (238, 238, 238, 125)
+            };
+            window_vibrancy::apply_acrylic(window, Some(tint))
+        }
+        WindowEffect::Vibrancy => window_vibrancy::apply_vibrancy(
+            window,
+            NSVisualEffectMaterial::HudWindow,
+            Some(NSVisualEffectState::FollowsWindowActiveState),
+            None,
+        ),
+        WindowEffect::Off | WindowEffect::Auto => Ok(()),
+    }
+}
+
+fn clear<R: Runtime>(window: &WebviewWindow<R>) {
+    let _ = window_vibrancy::clear_mica(window);
+    let _ = window_vibrancy::clear_acrylic(window);
+    let _ = window_vibrancy::clear_vibrancy(window);
+}
+
+// 应用窗口效果，返回实际生效的效果（全部失败时为 Off）
+pub fn apply_window_effect<R: Runtime>(
+    window: &WebviewWindow<R>,
+    effect: WindowEffect,
+    theme: Theme,
+) -> WindowEffect {
+    clear(window);
+
+    for &candidate in effect.candidates() {
+        match try_apply(window, candidate, theme) {
+            Ok(_) => return candidate,
+            Err(e) => println!("Window effect - {:?} unavailable: {}", candidate, e),
+        }
+    }
+
+    WindowEffect::Off
+}
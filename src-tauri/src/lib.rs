@@ -1,10 +1,65 @@
 // 导入托盘功能函数与窗口事件处理
-use crate::core::{tray::create_tray, window_handler::setup_window_close_handler};
+use crate::core::{
+    appearance::setup_appearance,
+    audio::AudioPlayerState,
+    clipboard_watcher::{restore_last_clip, setup_clipboard_watcher, ClipboardWatcherState},
+    db::open_db,
+    hotkeys::setup_hotkeys,
+    lock::{setup_lock, AppLock},
+    paster::PasteStack,
+    retention::setup_retention,
+    schedule::setup_schedule,
+    settings::setup_settings,
+    tray::{create_tray, refresh_tray_menu},
+    watch_folder::{setup_watch_folders, WatchFolderState},
+    window_handler::setup_window_close_handler,
+};
+use tauri::Manager;
 
 // 声明模块层次结构
 // 告诉编译器在 core 目录下有一个 tray.rs 文件作为模块
 mod core {
+    pub mod animated;
+    pub mod anomaly;
+    pub mod appearance;
+    pub mod audio;
+    pub mod clipboard_watcher;
+    pub mod db;
+    pub mod exif;
+    pub mod files;
+    pub mod hotkeys;
+    pub mod http;
+    pub mod image_edit;
+    pub mod images;
+    pub mod issue;
+    pub mod journal;
+    pub mod keystore;
+    pub mod link_preview;
+    pub mod lock;
+    pub mod merge;
+    pub mod origin;
+    pub mod pac;
+    pub mod paster;
+    pub mod previews;
+    pub mod qr;
+    pub mod redact;
+    pub mod retention;
+    pub mod rich_text;
+    pub mod rules;
+    pub mod schedule;
+    pub mod screenshot;
+    pub mod search;
+    pub mod sensitive;
+    pub mod settings;
+    pub mod shards;
+    pub mod snippets;
+    pub mod source_app;
+    pub mod svg;
+    pub mod tags;
+    pub mod transform;
     pub mod tray;
+    pub mod watch_folder;
+    pub mod window_effects;
     pub mod window_handler;
 }
 
@@ -12,9 +67,17 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_process::init()) // 创建默认的 Tauri 应用构建器
         .plugin(tauri_plugin_shell::init()) // 添加 shell 插件，允许应用与系统 shell 交互
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .manage(ClipboardWatcherState::default())
+        .manage(AudioPlayerState::default())
+        .manage(AppLock::default())
+        .manage(PasteStack::default())
+        .manage(WatchFolderState::default())
         .setup(|app| {
             // 设置回调函数，在应用初始化时执行 
             if cfg!(debug_assertions) {
@@ -27,16 +90,130 @@ pub fn run() {
                 )?;
             }
 
+            // 加载应用设置，其他模块初始化时会读取
+            setup_settings(app.handle());
+
             // 创建系统托盘
             // 调用我们在 tray.rs 中定义的 create_tray 函数
             // 传入应用句柄，函数会创建系统托盘并将其附加到应用
             create_tray(app.handle())?;
 
+            // 按外观设置创建主窗口并同步窗口主题
+            setup_appearance(app.handle());
+
             // 设置窗口关闭事件处理器
             setup_window_close_handler(app.handle());
 
+            // 注册显示/隐藏主窗口的全局快捷键
+            setup_hotkeys(app.handle());
+
+            // 打开历史数据库并执行迁移，监听线程启动前必须完成
+            app.manage(open_db(app.handle())?);
+
+            // 在托盘菜单中加入最近的记录
+            refresh_tray_menu(app.handle());
+
+            // 启动历史定时清理和每天的数据库维护
+            setup_retention(app.handle());
+            setup_schedule(app.handle());
+
+            // 以 --restore-last 启动时，把最近一条历史写回剪贴板
+            if std::env::args().any(|arg| arg == "--restore-last") {
+                restore_last_clip(app.handle());
+            }
+
+            // 在后台线程中启动剪贴板监听
+            setup_clipboard_watcher(app.handle());
+
+            // 开始监视设置中的文件夹，新文件保存为历史记录
+            setup_watch_folders(app.handle());
+
+            // 设置了锁定密码时以锁定状态启动，并开始检查空闲时间
+            // 需要在监听启动之后，锁定时才能按设置暂停监听
+            setup_lock(app.handle());
+
             Ok(()) // 返回 Ok 表示 setup 成功
         })
+        .invoke_handler(tauri::generate_handler![
+            crate::core::settings::get_settings,
+            crate::core::settings::update_settings,
+            crate::core::appearance::get_appearance,
+            crate::core::appearance::set_appearance,
+            crate::core::appearance::get_active_window_effect,
+            crate::core::clipboard_watcher::start_clipboard_watcher,
+            crate::core::clipboard_watcher::stop_clipboard_watcher,
+            crate::core::clipboard_watcher::is_clipboard_watcher_running,
+            crate::core::clipboard_watcher::get_capture_settings,
+            crate::core::clipboard_watcher::set_capture_settings,
+            crate::core::clipboard_watcher::add_excluded_app,
+            crate::core::clipboard_watcher::remove_excluded_app,
+            crate::core::source_app::get_running_apps,
+            crate::core::journal::export_journal,
+            crate::core::issue::copy_as_issue_body,
+            crate::core::issue::copy_as_issue_link,
+            crate::core::redact::redact_entry,
+            crate::core::merge::merge_items,
+            crate::core::transform::transform_item,
+            crate::core::sensitive::reveal_entry,
+            crate::core::db::get_history_stats,
+            crate::core::db::is_history_encrypted,
+            crate::core::db::set_history_encryption,
+            crate::core::db::get_history,
+            crate::core::db::delete_item,
+            crate::core::db::clear_history,
+            crate::core::db::get_pinned,
+            crate::core::db::pin_item,
+            crate::core::db::unpin_item,
+            crate::core::tags::add_tag,
+            crate::core::tags::remove_tag,
+            crate::core::tags::get_all_tags,
+            crate::core::retention::get_retention_policy,
+            crate::core::retention::set_retention_policy,
+            crate::core::retention::run_cleanup_now,
+            crate::core::schedule::get_next_run_times,
+            crate::core::db::get_query_plans,
+            crate::core::search::search_history,
+            crate::core::images::copy_image,
+            crate::core::image_edit::edit_image,
+            crate::core::rich_text::copy_item_as,
+            crate::core::qr::generate_qr,
+            crate::core::svg::copy_svg,
+            crate::core::files::copy_files,
+            crate::core::previews::get_file_preview,
+            crate::core::origin::open_origin,
+            crate::core::audio::play_entry,
+            crate::core::audio::stop_playback,
+            crate::core::hotkeys::get_hotkeys,
+            crate::core::hotkeys::set_hotkey,
+            crate::core::hotkeys::set_paste_plain_hotkey,
+            crate::core::hotkeys::set_paste_next_hotkey,
+            crate::core::hotkeys::set_item_hotkey,
+            crate::core::hotkeys::set_print_screen_mode,
+            crate::core::http::get_proxy_settings,
+            crate::core::http::set_proxy_settings,
+            crate::core::http::set_proxy_password,
+            crate::core::http::has_proxy_password,
+            crate::core::http::get_update_proxy,
+            crate::core::http::test_connection,
+            crate::core::paster::paste_item,
+            crate::core::paster::paste_plain,
+            crate::core::paster::stack_push,
+            crate::core::paster::stack_clear,
+            crate::core::paster::get_stack,
+            crate::core::paster::stack_paste_next,
+            crate::core::snippets::get_snippets,
+            crate::core::snippets::create_snippet,
+            crate::core::snippets::update_snippet,
+            crate::core::snippets::delete_snippet,
+            crate::core::snippets::expand_snippet,
+            crate::core::snippets::paste_snippet,
+            crate::core::lock::set_passcode,
+            crate::core::lock::has_passcode,
+            crate::core::lock::lock_app,
+            crate::core::lock::unlock_app,
+            crate::core::lock::is_app_locked,
+            crate::core::lock::report_activity,
+        ])
         .run(tauri::generate_context!()) // 运行应用，传入从 tauri.conf.json 生成的上下文
         .expect("error while running tauri application"); // 如果运行失败则 panic
 }
@@ -8,6 +8,13 @@ import ExitConfirmationModal from "./components/ExitConfirmationModal";
 // ✅ 新增：导入 Tauri updater 和 process
 import { check } from "@tauri-apps/plugin-updater";
 import { relaunch } from "@tauri-apps/plugin-process";
+import { invoke } from "@tauri-apps/api/core";
+
+// 手动代理时把代理地址传给 updater，系统代理由 updater 自行读取
+const checkUpdate = async () => {
+  const proxy = await invoke<string | null>("get_update_proxy");
+  return check(proxy ? { proxy } : undefined);
+};
 
 // 自定义更新确认对话框（轻量级）
 const UpdateDialog: React.FC<{
@@ -59,7 +66,7 @@ const AppWithVerification: React.FC = () => {
   // 🆕 新增：检查更新函数
   const checkForUpdate = async () => {
     try {
-      const update = await check();
+      const update = await checkUpdate();
       if (update?.shouldUpdate) {
         setUpdateAvailable({
           version: update.manifest?.version || "未知",
@@ -152,7 +159,7 @@ const AppWithVerification: React.FC = () => {
   // 🆕 新增：执行更新
   const handleUpdate = async () => {
     try {
-      const update = await check();
+      const update = await checkUpdate();
       if (!update?.shouldUpdate) return;
 
       showSuccess("正在下载更新...");